@@ -0,0 +1,86 @@
+//! Compares `Game::check_end` against walking every winning line tile by tile, and
+//! times a full search of the classic board
+//!
+//! Run with `cargo bench`
+use ric_rac_roe_game::game::ai::Engine;
+use ric_rac_roe_game::game::rng::Rng;
+use ric_rac_roe_game::game::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Plays random moves from an empty board, stopping at a random point before the end
+fn random_position(variant: Variant, rng: &mut Rng) -> Game {
+    let mut game = Game::with_variant(variant);
+    let stop = rng.below(variant.area());
+    while game.turn_history().len() < stop {
+        let moves: Vec<Coords> = game.legal_moves().collect();
+        let coords = *rng.choose(&moves).expect("The game is not over");
+        game.play_coords(coords).expect("Legal moves can be played");
+        if game.result().is_some() {
+            game.undo();
+            break;
+        }
+    }
+    game
+}
+
+/// Does the same as `Game::check_end` by walking every winning line tile by tile,
+/// as the game did before it kept tables of lines
+fn check_end_by_scan(game: &Game) -> Option<GameResult> {
+    if game.result().is_some() {
+        return *game.result();
+    }
+    let board = game.board();
+    for line in board.variant().win_lines() {
+        let first = *board.value_at_coords(&line[0]);
+        if first.is_some()
+            && line
+                .iter()
+                .all(|coords| *board.value_at_coords(coords) == first)
+        {
+            return first.map(GameResult::Winner);
+        }
+    }
+    if board.iter().all(|(_, value)| value.is_some()) {
+        return Some(GameResult::Tie);
+    }
+    None
+}
+
+/// Runs `f` repeatedly for about half a second and prints the average time per run
+fn bench(name: &str, mut f: impl FnMut()) {
+    let budget = Duration::from_millis(500);
+    let start = Instant::now();
+    let mut runs = 0u32;
+    while start.elapsed() < budget {
+        f();
+        runs += 1;
+    }
+    println!(
+        "{name:<40} {:>12.1} ns/run ({runs} runs)",
+        start.elapsed().as_nanos() as f64 / runs as f64
+    );
+}
+
+fn main() {
+    let mut rng = Rng::seed_from(0);
+    for variant in ["3x3k3", "4x4k4", "7x6k4", "15x15k5"] {
+        let variant: Variant = variant.parse().expect("is a valid variant");
+        let positions: Vec<Game> = (0..1000)
+            .map(|_| random_position(variant, &mut rng))
+            .collect();
+        bench(&format!("check_end {variant} x1000"), || {
+            for game in &positions {
+                black_box(black_box(game).check_end());
+            }
+        });
+        bench(&format!("check_end_by_scan {variant} x1000"), || {
+            for game in &positions {
+                black_box(check_end_by_scan(black_box(game)));
+            }
+        });
+    }
+    bench("solve 3x3k3 from an empty board", || {
+        black_box(Engine::new().best_move(black_box(&Game::new())));
+    });
+}
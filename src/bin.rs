@@ -1,78 +1,829 @@
+use ric_rac_roe_game::game::ai::{Computer, Difficulty, Engine};
+use ric_rac_roe_game::game::analysis::Analysis;
+use ric_rac_roe_game::game::cli::{
+    can_search, check_players, Command, Format, Options, PlayerKind,
+};
+use ric_rac_roe_game::game::mcts::{Budget, Mcts, Playout};
+use ric_rac_roe_game::game::menace::Menace;
+use ric_rac_roe_game::game::player::{MatchOutcome, Player};
+use ric_rac_roe_game::game::record::Record;
+use ric_rac_roe_game::game::review::Review;
+use ric_rac_roe_game::game::rng::Rng;
+use ric_rac_roe_game::game::series::{Seat, Series, SeriesStats, Tally};
 use ric_rac_roe_game::game::*;
-use std::io;
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::mem;
+use std::process;
+use std::time::Instant;
 
-fn prompt_move(g: &Game) -> Coords {
-    let mut coords: (u8, u8) = (0, 0);
-    let mut input: String;
-    let stdin = io::stdin();
-    loop {
-        input = String::new();
-        println!(
-            "Player {}, input the row you would like to play in(0, 1, or 2; e.g. 0 for top): ",
-            g.player_turn()
-        );
-        stdin.read_line(&mut input).expect("Failed to read line");
-        if let Ok(row @ 0..=2) = input.trim().parse::<u8>() {
-            coords.0 = row;
-        } else {
-            println!("Please enter a value between 0(top) and 2(bottom).");
-            continue;
+const USAGE: &str = "\
+Usage: ric_rac_roe_runner [OPTIONS]
+
+Options:
+  --player1 PLAYER  who the first player is: human, menace (a computer that learns
+                    from its games), or a computer playing at random, easy,
+                    medium, hard or perfect; medium and hard play by Monte
+                    Carlo tree search on boards larger than 20x20 and 9x9,
+                    and perfect only plays boards of up to 16 tiles
+                    [default: human]
+  --player2 PLAYER  who the second player is [default: human]
+  --first 1|2       which player plays X, and so moves first; a person playing a
+                    computer is asked when this is left out [default: 1]
+  --variant WxHkK   board width, height and win length, like 4x4k3 [default: 3x3k3]
+  --games N         number of games to play when both players are computers, who
+                    swap sides after every game [default: 1]
+  --seed N          seed for the computers' random choices, to make games repeatable
+  --format FORMAT   how finished games are reported: text, json or csv [default: text]
+  --load FILE       continue the game saved in FILE; computers start every game from it
+  --menace FILE     keep what menace learns in FILE, loading it first if it exists
+  --train N         let menace play N games against itself before playing [default: 0]
+  --help            show this message";
+
+const HELP: &str = "\
+Type a tile to play there, like b2 (column letter, row number), 1,1 (row, column) or 5 (numpad), or one of:
+  undo          take back the last move
+  hint          suggest a move and show what every move is worth
+  review        check every move so far against perfect play
+  save <file>   save the game record to <file>, with any blunders from the last review
+  load <file>   load a game record from <file>
+  resign        give up the game
+  new           start a new game
+  rematch       start a new game with X and O swapped
+  board         show the board
+  history       list the moves played so far
+  help          show this message
+  quit          leave";
+
+/// Searching to the end of the game is only quick with this many open tiles or fewer
+const PERFECT_HINT_TILES: usize = 10;
+
+/// Playouts per move for computers on boards too large for their own search
+const LARGE_BOARD_ITERATIONS: u32 = 500;
+
+/// Prints finished games in the chosen `Format`
+///
+/// JSON and CSV output is one row per line, all with the same fields: `type` says
+/// whether a row is a game, a player's results over a series, or the results of a
+/// first move, and fields that do not apply to that type are left out or empty
+struct Reporter {
+    format: Format,
+    games: u32,
+    rows: u32,
+}
+
+/// The fields of every row in JSON and CSV output, in CSV column order
+const COLUMNS: [&str; 16] = [
+    "type",
+    "game",
+    "variant",
+    "x",
+    "o",
+    "result",
+    "moves",
+    "player",
+    "name",
+    "split",
+    "move",
+    "games",
+    "wins",
+    "draws",
+    "losses",
+    "average_length",
+];
+
+/// One value in a row of `Reporter` output
+enum Field {
+    Text(String),
+    Number(String),
+    List(Vec<String>),
+}
+
+impl Reporter {
+    fn new(format: Format) -> Self {
+        Self {
+            format,
+            games: 0,
+            rows: 0,
         }
-        input = String::new();
-        println!(
-            "Player {}, input the column you would like to play in(0, 1, or 2; e.g. 0 for left): ",
-            g.player_turn()
-        );
-        stdin.read_line(&mut input).expect("Failed to read line");
-        if let Ok(col @ 0..=2) = input.trim().parse::<u8>() {
-            coords.1 = col;
-        } else {
-            println!("Please enter a value between 0(left) and 2(right).");
-            continue;
+    }
+
+    fn report(&mut self, record: &Record) {
+        self.games += 1;
+        let tag = |name| record.tag(name).unwrap_or_default().to_string();
+        let game = record.game();
+        let moves: Vec<String> = game
+            .turn_history()
+            .iter()
+            .map(|turn| turn.coords().to_string())
+            .collect();
+        if self.format == Format::Text {
+            let outcome = game
+                .result()
+                .map_or("unfinished".to_string(), |result| result.to_string());
+            println!(
+                "Game {}, {} (X) vs {} (O): {outcome} after {} moves",
+                self.games,
+                tag("X"),
+                tag("O"),
+                moves.len()
+            );
+            return;
+        }
+        self.row(&[
+            ("type", Field::Text("game".to_string())),
+            ("game", Field::Number(self.games.to_string())),
+            ("variant", Field::Text(game.board().variant().to_string())),
+            ("x", Field::Text(tag("X"))),
+            ("o", Field::Text(tag("O"))),
+            ("result", Field::Text(tag("Result"))),
+            ("moves", Field::List(moves)),
+        ]);
+    }
+
+    /// Prints the statistics for a series between `names[0]`, who played X first, and `names[1]`
+    fn summarize(&mut self, names: &[String; 2], stats: &SeriesStats) {
+        if self.format == Format::Text {
+            println!();
+            println!("Player one is {} and player two is {}.", names[0], names[1]);
+            println!("{stats}");
+            return;
+        }
+        let one = [
+            ("overall", stats.overall),
+            ("as X", stats.as_x),
+            ("as O", stats.as_o),
+        ];
+        let two = [
+            ("overall", stats.overall.reversed()),
+            ("as X", stats.as_o.reversed()),
+            ("as O", stats.as_x.reversed()),
+        ];
+        for (number, name, tallies) in [(1, &names[0], one), (2, &names[1], two)] {
+            for (split, tally) in tallies {
+                let mut row = vec![
+                    ("type", Field::Text("player".to_string())),
+                    ("player", Field::Number(number.to_string())),
+                    ("name", Field::Text(name.clone())),
+                    ("split", Field::Text(split.to_string())),
+                ];
+                row.extend(tally_fields(&tally));
+                self.row(&row);
+            }
+        }
+        for (coords, tally) in &stats.first_moves {
+            let mut row = vec![
+                ("type", Field::Text("first_move".to_string())),
+                ("move", Field::Text(coords.to_string())),
+            ];
+            row.extend(tally_fields(tally));
+            self.row(&row);
+        }
+    }
+
+    /// Prints one row of JSON or CSV output, with the CSV header before the first
+    fn row(&mut self, fields: &[(&str, Field)]) {
+        self.rows += 1;
+        match self.format {
+            Format::Text => {}
+            Format::Json => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, field)| {
+                        let value = match field {
+                            Field::Text(text) => json_string(text),
+                            Field::Number(number) => number.clone(),
+                            Field::List(items) => {
+                                let items: Vec<String> =
+                                    items.iter().map(|item| json_string(item)).collect();
+                                format!("[{}]", items.join(","))
+                            }
+                        };
+                        format!("{}:{value}", json_string(name))
+                    })
+                    .collect();
+                println!("{{{}}}", fields.join(","));
+            }
+            Format::Csv => {
+                if self.rows == 1 {
+                    println!("{}", COLUMNS.join(","));
+                }
+                let cells: Vec<String> = COLUMNS
+                    .iter()
+                    .map(
+                        |column| match fields.iter().find(|(name, _)| name == column) {
+                            Some((_, Field::Text(text) | Field::Number(text))) => text.clone(),
+                            Some((_, Field::List(items))) => items.join(" "),
+                            None => String::new(),
+                        },
+                    )
+                    .collect();
+                println!("{}", cells.join(","));
+            }
+        }
+    }
+}
+
+fn tally_fields(tally: &Tally) -> [(&'static str, Field); 5] {
+    [
+        ("games", Field::Number(tally.games.to_string())),
+        ("wins", Field::Number(tally.wins.to_string())),
+        ("draws", Field::Number(tally.draws.to_string())),
+        ("losses", Field::Number(tally.losses.to_string())),
+        (
+            "average_length",
+            Field::Number(format!("{:.2}", tally.average_length())),
+        ),
+    ]
+}
+
+/// Writes `text` as a JSON string, quoted and with quotes, backslashes and control
+/// characters escaped
+fn json_string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// Hands each computer its own random number generator, all drawn from one seed
+fn computer(difficulty: Difficulty, seeds: &mut Rng) -> Computer {
+    Computer::new(difficulty, Rng::seed_from(seeds.next_u64()))
+}
+
+/// Tells which of `players` play by Monte Carlo tree search on `variant`
+fn note_engines(players: &[PlayerKind], variant: &Variant) {
+    for kind in players {
+        if let PlayerKind::Computer(difficulty) = kind {
+            if !can_search(*difficulty, variant) {
+                eprintln!(
+                    "{kind} plays by Monte Carlo tree search on {variant}, which is too large for its own search."
+                );
+            }
+        }
+    }
+}
+
+/// Loads MENACE from `options.menace` if the file exists, or starts a new one, and
+/// trains it for `options.train` games
+fn load_menace(options: &Options, variant: Variant, seeds: &mut Rng) -> Result<Menace, String> {
+    let rng = Rng::seed_from(seeds.next_u64());
+    let mut menace = match options.menace.as_deref().map(fs::File::open) {
+        Some(Ok(file)) => Menace::read_from(file, rng).map_err(|error| error.to_string())?,
+        Some(Err(error)) if error.kind() != io::ErrorKind::NotFound => {
+            return Err(error.to_string())
+        }
+        _ => Menace::new(rng),
+    };
+    menace.train(variant, options.train);
+    Ok(menace)
+}
+
+fn save_menace(file: &str, menace: &Menace) {
+    let saved = fs::File::create(file)
+        .map(io::BufWriter::new)
+        .and_then(|writer| menace.write_to(writer));
+    if let Err(error) = saved {
+        eprintln!("Could not save menace to {file}: {error}");
+    }
+}
+
+fn load_record(file: &str) -> Result<Record, String> {
+    let text = fs::read_to_string(file).map_err(|error| error.to_string())?;
+    text.parse()
+        .map_err(|error: record::RecordError| error.to_string())
+}
+
+/// Who is playing one side of the game in the runner
+enum Side {
+    Human,
+    Menace(Menace),
+    /// A computer, with the Monte Carlo tree search it plays by instead on boards its
+    /// own search would not finish on
+    Computer(Computer, Mcts),
+}
+
+/// Lets computer sides play through a `Series`; people never do
+impl Player for Side {
+    fn choose_move(&mut self, game: &Game) -> Coords {
+        match self {
+            Side::Human => unreachable!("People's moves are typed in"),
+            Side::Menace(menace) => menace.choose_move(game),
+            Side::Computer(computer, mcts)
+                if !can_search(*computer.difficulty(), game.board().variant()) =>
+            {
+                mcts.choose_move(game)
+            }
+            Side::Computer(computer, _) => computer.choose_move(game),
+        }
+    }
+
+    fn game_over(&mut self, game: &Game, result: &GameResult) {
+        match self {
+            Side::Human => {}
+            Side::Menace(menace) => menace.game_over(game, result),
+            Side::Computer(computer, mcts) => {
+                computer.game_over(game, result);
+                mcts.game_over(game, result);
+            }
+        }
+    }
+}
+
+impl Side {
+    /// Makes the side for `kind`, taking `menace` if it is MENACE's
+    fn new(kind: PlayerKind, seeds: &mut Rng, menace: &mut Option<Menace>) -> Self {
+        match kind {
+            PlayerKind::Human => Side::Human,
+            PlayerKind::Menace => Side::Menace(menace.take().expect("MENACE was loaded")),
+            PlayerKind::Computer(difficulty) => {
+                let computer = computer(difficulty, seeds);
+                let rng = Rng::seed_from(seeds.next_u64());
+                let mcts = Mcts::new(
+                    Budget::Iterations(LARGE_BOARD_ITERATIONS),
+                    Playout::Heuristic,
+                    rng,
+                );
+                Side::Computer(computer, mcts)
+            }
+        }
+    }
+
+    fn is_human(&self) -> bool {
+        matches!(self, Side::Human)
+    }
+
+    fn menace(&self) -> Option<&Menace> {
+        match self {
+            Side::Menace(menace) => Some(menace),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> PlayerKind {
+        match self {
+            Side::Human => PlayerKind::Human,
+            Side::Menace(_) => PlayerKind::Menace,
+            Side::Computer(computer, _) => PlayerKind::Computer(*computer.difficulty()),
+        }
+    }
+
+    fn name(&self) -> String {
+        self.kind().to_string()
+    }
+}
+
+/// The game being played in the runner, along with who resigned it, if anyone did
+struct Session {
+    game: Game,
+    variant: Variant,
+    resigned: Option<TileValue>,
+    x: Side,
+    o: Side,
+    engine: Engine,
+    reporter: Reporter,
+    /// The last review asked for, which is written into saved records while it still
+    /// covers the game
+    review: Option<Review>,
+    /// File MENACE is saved to after every game, if it is playing
+    menace_file: Option<String>,
+}
+
+impl Session {
+    fn new(options: &Options, seeds: &mut Rng, mut menace: Option<Menace>) -> Self {
+        Self {
+            game: Game::with_variant(options.variant),
+            variant: options.variant,
+            resigned: None,
+            x: Side::new(options.x(), seeds, &mut menace),
+            o: Side::new(options.o(), seeds, &mut menace),
+            engine: Engine::new(),
+            reporter: Reporter::new(options.format),
+            review: None,
+            menace_file: options.menace.clone(),
+        }
+    }
+
+    fn side(&mut self, value: TileValue) -> &mut Side {
+        match value {
+            TileValue::X => &mut self.x,
+            TileValue::O => &mut self.o,
+        }
+    }
+
+    fn is_over(&self) -> bool {
+        self.game.result().is_some() || self.resigned.is_some()
+    }
+
+    fn announce_result(&self) {
+        match (self.resigned, self.game.result()) {
+            (Some(loser), _) => println!("{loser} resigns, {} wins!", loser.toggle()),
+            (None, Some(GameResult::Tie)) => println!("It's a tie!"),
+            (None, Some(GameResult::Winner(winner))) => println!("{winner} wins!"),
+            (None, None) => {}
+        }
+    }
+
+    /// Announces a game that has just ended, and reports it if another format was asked for
+    fn finish(&mut self) {
+        self.announce_result();
+        // A resignation counts as a loss, so MENACE learns from those too
+        let result = match self.resigned {
+            Some(loser) => Some(GameResult::Winner(loser.toggle())),
+            None => *self.game.result(),
+        };
+        if let Some(result) = result {
+            self.x.game_over(&self.game, &result);
+            self.o.game_over(&self.game, &result);
+        }
+        if let Some(file) = &self.menace_file {
+            if let Some(menace) = self.x.menace().or(self.o.menace()) {
+                save_menace(file, menace);
+            }
+        }
+        if self.reporter.format != Format::Text {
+            let record = self.record();
+            self.reporter.report(&record);
         }
-        input = String::new();
         println!(
-            "Do you want to put your {} in tile ({},{}) (y or n)? ",
-            g.player_turn(),
-            coords.0,
-            coords.1
+            "Type rematch to play again as {}, or new to keep the same sides.",
+            self.rematch_sides()
         );
-        stdin.read_line(&mut input).expect("Failed to read line");
-        if input.trim().to_lowercase() == "y" {
-            return Coords::build(coords.0, coords.1)
-                .expect("Values were bounds checked, so they shouldn't be out of [0,2]");
+    }
+
+    /// Describes who plays which side after a rematch
+    fn rematch_sides(&self) -> String {
+        match (&self.x, &self.o) {
+            (x, o) if x.is_human() && !o.is_human() => "O".to_string(),
+            (x, o) if !x.is_human() && o.is_human() => "X".to_string(),
+            (x, o) => format!("{} (X) vs {} (O)", o.name(), x.name()),
+        }
+    }
+
+    fn record(&self) -> Record {
+        let mut record = Record::new(self.game.clone());
+        record.set_tag("X", &self.x.name());
+        record.set_tag("O", &self.o.name());
+        if let Some(loser) = self.resigned {
+            tag_resignation(&mut record, loser);
+        }
+        if let Some(review) = &self.review {
+            let reviewed = review.turns.iter().map(|turn| &turn.turn);
+            if reviewed.eq(self.game.turn_history()) {
+                review.annotate(&mut record);
+            }
+        }
+        record
+    }
+
+    fn load(&mut self, record: Record) {
+        self.resigned = None;
+        if record.tag("Termination") == Some("resignation") && record.game().result().is_none() {
+            self.resigned = match record.tag("Result") {
+                Some("1-0") => Some(TileValue::O),
+                Some("0-1") => Some(TileValue::X),
+                _ => None,
+            };
+        }
+        self.game = record.game().clone();
+        self.variant = *self.game.board().variant();
+    }
+
+    fn hint(&mut self) -> Analysis {
+        let depth = if self.game.legal_moves().count() <= PERFECT_HINT_TILES {
+            None
         } else {
-            continue;
+            Some(2)
+        };
+        self.engine.analyze(&self.game, depth)
+    }
+
+    /// Plays the computers' moves until it is a person's turn or the game is over
+    fn let_computers_move(&mut self) {
+        while !self.is_over() {
+            let value = *self.game.player_turn();
+            let side = match value {
+                TileValue::X => &mut self.x,
+                TileValue::O => &mut self.o,
+            };
+            let started = Instant::now();
+            if side.is_human() {
+                return;
+            }
+            let coords = side.choose_move(&self.game);
+            let thinking = started.elapsed();
+            self.game
+                .play_coords(coords)
+                .expect("Computers only choose open tiles");
+            println!(
+                "{value} plays {coords} (thought for {:.1} ms).",
+                thinking.as_secs_f64() * 1000.0
+            );
+            println!("{}", self.game);
+            if self.is_over() {
+                self.finish();
+            }
         }
     }
-}
 
-fn play() {
-    let mut g: Game = Game::new();
-    loop{
-        println!("{}", g);
-        match g.play_coords(prompt_move(&g)){
-            Ok(None) => continue,
-            Ok(Some(GameResult::Tie)) => {
-                println!("It's a tie!");
-                break;
+    fn run(&mut self, command: Command) {
+        match command {
+            Command::Play(_) | Command::Hint | Command::Resign if self.is_over() => {
+                println!("The game is over; type new to start another or undo to take back the last move.");
+            }
+            Command::Play(coords) => match self.game.play_coords(coords) {
+                Ok(result) => {
+                    println!("{}", self.game);
+                    if result.is_some() {
+                        self.finish();
+                    }
+                }
+                Err(error) => println!("{error}"),
+            },
+            Command::Undo if self.resigned.is_some() => {
+                self.resigned = None;
+                println!("Resignation taken back.");
+            }
+            Command::Undo => match self.game.undo().cloned() {
+                Some(mut turn) => {
+                    // Taking back only a computer's move would just let it play again
+                    while !self.side(*self.game.player_turn()).is_human() {
+                        match self.game.undo() {
+                            Some(earlier) => turn = earlier.clone(),
+                            None => break,
+                        }
+                    }
+                    println!("Took back {} at {}.", turn.value(), turn.coords());
+                    println!("{}", self.game);
+                }
+                None => println!("There are no moves to take back."),
+            },
+            Command::Hint => {
+                let analysis = self.hint();
+                if let Some(best) = analysis.best() {
+                    println!("Try {} ({}).", best.coords, best.score);
+                }
+                println!("{analysis}");
+            }
+            Command::Review => {
+                // Reviews start from the empty board, so only small boards are searched to the end
+                let depth = if self.variant.area() <= PERFECT_HINT_TILES {
+                    None
+                } else {
+                    Some(2)
+                };
+                let review = self.engine.review(&self.game, depth);
+                if review.turns.is_empty() {
+                    println!("No moves have been played yet.");
+                } else {
+                    println!("{review}");
+                    match review.blunders().count() {
+                        0 => println!("No blunders."),
+                        count => println!("{count} blunder(s); save to keep them in the record."),
+                    }
+                }
+                self.review = Some(review);
             }
-            Ok(Some(GameResult::Winner(winner))) => {
-                println!("{winner} wins!");
-                break;
+            Command::Save(file) => match fs::write(&file, self.record().to_string()) {
+                Ok(()) => println!("Saved to {file}."),
+                Err(error) => println!("Could not save to {file}: {error}"),
+            },
+            Command::Load(file) => match load_record(&file) {
+                Ok(record) => {
+                    let players = [self.x.kind(), self.o.kind()];
+                    let variant = *record.game().board().variant();
+                    if let Err(message) = check_players(&players, &variant) {
+                        println!("Could not load {file}: {message}");
+                        return;
+                    }
+                    note_engines(&players, &variant);
+                    self.load(record);
+                    println!("{}", self.game);
+                    self.announce_result();
+                }
+                Err(error) => println!("Could not load {file}: {error}"),
+            },
+            Command::Resign => {
+                self.resigned = Some(*self.game.player_turn());
+                self.finish();
             }
-            Err(TurnError::GameOver(_)) => {
-                println!("Game is already over?");
-                break;
+            Command::New => {
+                self.game = Game::with_variant(self.variant);
+                self.resigned = None;
+                println!("{}", self.game);
             }
-            Err(TurnError::TileFull(value)) => {
-                println!("{value} is already in that spot!");
+            Command::Rematch => {
+                mem::swap(&mut self.x, &mut self.o);
+                self.game = Game::with_variant(self.variant);
+                self.resigned = None;
+                println!("{} plays X and {} plays O.", self.x.name(), self.o.name());
+                println!("{}", self.game);
             }
+            Command::Board => {
+                println!("{}", self.game);
+                self.announce_result();
+            }
+            Command::History => {
+                if self.game.turn_history().is_empty() {
+                    println!("No moves have been played yet.");
+                }
+                for (ply, turn) in self.game.turn_history().iter().enumerate() {
+                    println!("{}. {} {}", ply + 1, turn.value(), turn.coords());
+                }
+            }
+            Command::Help => println!("{HELP}"),
+            Command::Quit => {}
+        }
+    }
+}
+
+/// Prints `prompt` and reads a line, returning `None` once input has run out
+fn read_line(input: &mut impl BufRead, prompt: &str) -> Option<String> {
+    print!("{prompt}");
+    io::stdout().flush().expect("Failed to flush stdout");
+    let mut line = String::new();
+    match input.read_line(&mut line) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(line),
+    }
+}
+
+/// Asks a person playing a computer whether they want to be X or O, returning the
+/// index into `players` of whoever will play X, or `None` if input ran out
+fn choose_first(input: &mut impl BufRead, players: &[PlayerKind; 2]) -> Option<usize> {
+    let human = players.iter().position(|&kind| kind == PlayerKind::Human)?;
+    loop {
+        let line = read_line(input, "Do you want to play X (moving first) or O? ")?;
+        match line.trim().to_lowercase().as_str() {
+            "x" => return Some(human),
+            "o" => return Some(1 - human),
+            _ => println!("Please type X or O."),
+        }
+    }
+}
+
+/// Plays with at least one person at the keyboard, taking commands until they quit
+fn play(options: &Options, loaded: Option<Record>, seeds: &mut Rng, menace: Option<Menace>) {
+    let mut input = io::stdin().lock();
+    let mut options = options.clone();
+    let against_computer = options
+        .players
+        .iter()
+        .any(|&kind| kind != PlayerKind::Human);
+    if options.first.is_none() && loaded.is_none() && against_computer {
+        match choose_first(&mut input, &options.players) {
+            Some(first) => options.first = Some(first),
+            None => {
+                println!();
+                return;
+            }
+        }
+    }
+    let mut session = Session::new(&options, seeds, menace);
+    match loaded {
+        Some(record) => {
+            session.load(record);
+            println!("{}", session.game);
+            session.announce_result();
+        }
+        None => println!("{}", session.game),
+    }
+    println!("Type help for a list of commands.");
+    session.let_computers_move();
+    loop {
+        let prompt = if session.is_over() {
+            "> ".to_string()
+        } else {
+            format!("{}> ", session.game.player_turn())
+        };
+        let Some(line) = read_line(&mut input, &prompt) else {
+            println!();
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.parse() {
+            Ok(Command::Quit) => break,
+            Ok(command) => {
+                session.run(command);
+                session.let_computers_move();
+            }
+            Err(message) => println!("{message}"),
+        }
+    }
+}
+
+/// Marks `record` as won by the other side after `loser` resigned
+fn tag_resignation(record: &mut Record, loser: TileValue) {
+    let result = match loser {
+        TileValue::X => "0-1",
+        TileValue::O => "1-0",
+    };
+    record.set_tag("Result", result);
+    record.set_tag("Termination", "resignation");
+}
+
+/// Plays `options.games` games between two computers, reporting each as it ends
+fn play_computers(
+    options: &Options,
+    loaded: Option<Record>,
+    seeds: &mut Rng,
+    mut menace: Option<Menace>,
+) {
+    let mut one = Side::new(options.x(), seeds, &mut menace);
+    let mut two = Side::new(options.o(), seeds, &mut menace);
+    let start = match loaded {
+        Some(record) => record.game().clone(),
+        None => Game::with_variant(options.variant),
+    };
+    let series = Series::play(&mut one, &mut two, &start, options.games);
+    let names = [options.x().to_string(), options.o().to_string()];
+    let mut reporter = Reporter::new(options.format);
+    for played in series.games() {
+        let mut record = Record::new(played.game.clone());
+        let (x, o) = match played.x {
+            Seat::One => (&names[0], &names[1]),
+            Seat::Two => (&names[1], &names[0]),
+        };
+        record.set_tag("X", x);
+        record.set_tag("O", o);
+        if let MatchOutcome::Resigned(loser) = played.outcome {
+            tag_resignation(&mut record, loser);
+        }
+        reporter.report(&record);
+    }
+    reporter.summarize(&names, &series.stats());
+    if let Some(file) = &options.menace {
+        if let Some(menace) = one.menace().or(two.menace()) {
+            save_menace(file, menace);
         }
     }
-    println!("{g}");
 }
 
 fn main() {
-    play()
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+    let loaded = match options.load.as_deref().map(load_record).transpose() {
+        Ok(loaded) => loaded,
+        Err(message) => {
+            eprintln!(
+                "Could not load {}: {message}",
+                options.load.as_deref().unwrap_or_default()
+            );
+            process::exit(1);
+        }
+    };
+    let variant = loaded
+        .as_ref()
+        .map_or(options.variant, |record| *record.game().board().variant());
+    if let Err(message) = check_players(&options.players, &variant) {
+        eprintln!(
+            "Could not load {}: {message}",
+            options.load.as_deref().unwrap_or_default()
+        );
+        process::exit(1);
+    }
+    note_engines(&options.players, &variant);
+    let mut seeds = options.seed.map_or_else(Rng::from_entropy, Rng::seed_from);
+    let menace = if options.players.contains(&PlayerKind::Menace) {
+        match load_menace(&options, variant, &mut seeds) {
+            Ok(menace) => Some(menace),
+            Err(message) => {
+                eprintln!(
+                    "Could not load menace from {}: {message}",
+                    options.menace.as_deref().unwrap_or_default()
+                );
+                process::exit(1);
+            }
+        }
+    } else {
+        None
+    };
+    if options.players.contains(&PlayerKind::Human) {
+        play(&options, loaded, &mut seeds, menace)
+    } else {
+        play_computers(&options, loaded, &mut seeds, menace)
+    }
 }
@@ -0,0 +1,510 @@
+//! Computer opponents for Tic-Tac-Toe, from perfect play down to random moves
+use super::rng::Rng;
+use super::{Coords, Game, GameResult, TileValue, Variant};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+/// The game-theoretic value of a position for the player whose turn it is,
+/// assuming perfect play from both sides
+///
+/// Wins and losses carry the number of plies (single turns) until the game ends.
+/// Searches limited in depth score positions beyond their horizon as a `Draw`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Score {
+    Win(u8),
+    Draw,
+    Loss(u8),
+}
+
+impl Score {
+    /// Value given to a win on the very next ply; every further ply costs one point
+    const WIN: i32 = 1000;
+
+    fn from_internal(value: i32) -> Self {
+        match value.cmp(&0) {
+            Ordering::Greater => Score::Win((Self::WIN - value) as u8),
+            Ordering::Less => Score::Loss((Self::WIN + value) as u8),
+            Ordering::Equal => Score::Draw,
+        }
+    }
+
+    fn to_internal(self) -> i32 {
+        match self {
+            Score::Win(plies) => Self::WIN - plies as i32,
+            Score::Loss(plies) => -(Self::WIN - plies as i32),
+            Score::Draw => 0,
+        }
+    }
+}
+
+impl Ord for Score {
+    /// Orders scores from the point of view of the player to move, so faster wins
+    /// are greater than slower ones and slower losses are greater than faster ones
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.to_internal().cmp(&other.to_internal())
+    }
+}
+
+impl PartialOrd for Score {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Score::Win(plies) => write!(f, "win in {plies}"),
+            Score::Draw => write!(f, "draw"),
+            Score::Loss(plies) => write!(f, "loss in {plies}"),
+        }
+    }
+}
+
+/// The move an `Engine` picked and how it expects the game to go from there
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BestMove {
+    pub coords: Coords,
+    pub score: Score,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Entry {
+    value: i32,
+    bound: Bound,
+    depth: u8,
+}
+
+/// Search depth meaning "search until the game ends"
+const UNLIMITED: u8 = u8::MAX;
+
+/// A negamax searcher with alpha-beta pruning and a transposition table
+///
+/// Searching to the end of the game is only practical on small boards; larger
+/// variants should use `best_move_to_depth`
+///
+/// The transposition table is kept between searches, so reusing one `Engine` for
+/// a whole game is much faster than making a new one every move
+#[derive(Debug, Default)]
+pub struct Engine {
+    table: HashMap<PositionKey, Entry>,
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Finds the best move for `game.player_turn()`, or `None` if the game is over
+    ///
+    /// `game` is cloned for the search and is never modified
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::ai::*;
+    /// let mut g = Game::new();
+    /// for c in [(0, 0), (1, 0), (0, 1)] {
+    ///     g.play_coords(Coords::build(c.0, c.1).expect("is in bounds")).expect("This tile is open and the game is not over");
+    /// }
+    /// let mut engine = Engine::new();
+    /// let best = engine.best_move(&g).expect("The game is not over");
+    /// assert_eq!(best.coords, Coords::build(0, 2).expect("is in bounds"));
+    /// assert_eq!(best.score, Score::Loss(4));
+    /// assert_eq!(g.turn_history().len(), 3);
+    /// g.play_coords(Coords::build(2, 2).expect("is in bounds")).expect("This tile is open and the game is not over");
+    /// let best = engine.best_move(&g).expect("The game is not over");
+    /// assert_eq!(best.coords, Coords::build(0, 2).expect("is in bounds"));
+    /// assert_eq!(best.score, Score::Win(1));
+    /// ```
+    pub fn best_move(&mut self, game: &Game) -> Option<BestMove> {
+        self.search_root(game, UNLIMITED)
+    }
+
+    /// Like `best_move`, but only looks `depth` plies ahead
+    ///
+    /// With a depth of 0 nothing is searched, so the first open tile is returned as a draw
+    pub fn best_move_to_depth(&mut self, game: &Game, depth: u8) -> Option<BestMove> {
+        self.search_root(game, depth.min(UNLIMITED - 1))
+    }
+
+    /// Scores every open tile in `game` for `game.player_turn()`, searching `depth`
+    /// plies ahead or to the end of the game if `depth` is `None`
+    ///
+    /// Unlike `best_move`, every score is exact rather than just good enough to rule
+    /// the move out, so moves can be compared against each other. With a depth of 0
+    /// no move is looked at, so every move scores a draw
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::ai::*;
+    /// let mut g = Game::new();
+    /// g.play_coords(Coords::build(0, 0).expect("is in bounds")).expect("This tile is open and the game is not over");
+    /// let moves = Engine::new().rank_moves(&g, None);
+    /// assert_eq!(moves.len(), 8);
+    /// let center = moves.iter().find(|m| m.coords == Coords::build(1, 1).expect("is in bounds")).expect("Center is open");
+    /// assert_eq!(center.score, Score::Draw);
+    /// let edge = moves.iter().find(|m| m.coords == Coords::build(0, 1).expect("is in bounds")).expect("Edge is open");
+    /// assert!(matches!(edge.score, Score::Loss(_)));
+    ///
+    /// let blind = Engine::new().rank_moves(&g, Some(0));
+    /// assert!(blind.iter().all(|m| m.score == Score::Draw));
+    /// let first = Engine::new().best_move_to_depth(&Game::new(), 0).expect("The game is not over");
+    /// assert_eq!((first.coords, first.score), (Coords::build(0, 0).expect("is in bounds"), Score::Draw));
+    /// ```
+    pub fn rank_moves(&mut self, game: &Game, depth: Option<u8>) -> Vec<BestMove> {
+        let depth = depth.map_or(UNLIMITED, |depth| depth.min(UNLIMITED - 1));
+        let mut game = game.clone();
+        let moves: Vec<Coords> = game.legal_moves().collect();
+        if depth == 0 {
+            return moves
+                .into_iter()
+                .map(|coords| BestMove {
+                    coords,
+                    score: Score::Draw,
+                })
+                .collect();
+        }
+        moves
+            .into_iter()
+            .map(|coords| {
+                game.play_coords(coords)
+                    .expect("Open tiles should be playable while the game is not over");
+                let value = -self.negamax(
+                    &mut game,
+                    1,
+                    child_depth(depth),
+                    -Score::WIN - 1,
+                    Score::WIN + 1,
+                );
+                game.undo();
+                BestMove {
+                    coords,
+                    score: Score::from_internal(value),
+                }
+            })
+            .collect()
+    }
+
+    fn search_root(&mut self, game: &Game, depth: u8) -> Option<BestMove> {
+        if game.result().is_some() {
+            return None;
+        }
+        if depth == 0 {
+            return game.legal_moves().next().map(|coords| BestMove {
+                coords,
+                score: Score::Draw,
+            });
+        }
+        let mut game = game.clone();
+        let mut best: Option<(Coords, i32)> = None;
+        let mut alpha = -Score::WIN - 1;
+        let moves: Vec<Coords> = game.legal_moves().collect();
+        for coords in moves {
+            game.play_coords(coords)
+                .expect("Open tiles should be playable while the game is not over");
+            let value = -self.negamax(&mut game, 1, child_depth(depth), -Score::WIN - 1, -alpha);
+            game.undo();
+            if best.is_none_or(|(_, best_value)| value > best_value) {
+                best = Some((coords, value));
+                alpha = alpha.max(value);
+            }
+        }
+        best.map(|(coords, value)| BestMove {
+            coords,
+            score: Score::from_internal(value),
+        })
+    }
+
+    /// Evaluates `game` for `game.player_turn()` under perfect play
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::ai::*;
+    /// assert_eq!(Engine::new().evaluate(&Game::new()), Score::Draw);
+    /// ```
+    pub fn evaluate(&mut self, game: &Game) -> Score {
+        let mut game = game.clone();
+        Score::from_internal(self.negamax(&mut game, 0, UNLIMITED, -Score::WIN - 1, Score::WIN + 1))
+    }
+
+    /// Returns the value of `game` for its player to move, with wins and losses counted
+    /// in plies from the root of the search, `ply` turns above this position
+    fn negamax(
+        &mut self,
+        game: &mut Game,
+        ply: i32,
+        depth: u8,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        if let Some(result) = *game.result() {
+            return from_table(terminal_value(result, *game.player_turn()), ply);
+        }
+        if depth == 0 {
+            return 0;
+        }
+        let key = position_key(game);
+        let original_alpha = alpha;
+        if let Some(entry) = self.table.get(&key).filter(|entry| entry.depth >= depth) {
+            let value = from_table(entry.value, ply);
+            match entry.bound {
+                Bound::Exact => return value,
+                Bound::Lower => alpha = alpha.max(value),
+                Bound::Upper => beta = beta.min(value),
+            }
+            if alpha >= beta {
+                return value;
+            }
+        }
+        let mut best = -Score::WIN - 1;
+        let moves: Vec<Coords> = game.legal_moves().collect();
+        for coords in moves {
+            game.play_coords(coords)
+                .expect("Open tiles should be playable while the game is not over");
+            let value = -self.negamax(game, ply + 1, child_depth(depth), -beta, -alpha);
+            game.undo();
+            best = best.max(value);
+            alpha = alpha.max(value);
+            if alpha >= beta {
+                break;
+            }
+        }
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(
+            key,
+            Entry {
+                value: to_table(best, ply),
+                bound,
+                depth,
+            },
+        );
+        best
+    }
+}
+
+/// Scores a finished game for `player_turn`, who would have moved next
+fn terminal_value(result: GameResult, player_turn: TileValue) -> i32 {
+    match result {
+        GameResult::Winner(winner) if winner == player_turn => Score::WIN,
+        GameResult::Winner(_) => -Score::WIN,
+        GameResult::Tie => 0,
+    }
+}
+
+fn child_depth(depth: u8) -> u8 {
+    if depth == UNLIMITED {
+        UNLIMITED
+    } else {
+        depth - 1
+    }
+}
+
+/// Converts a value counted from the root of the search into one counted from the
+/// position `ply` turns in, so it can be shared between searches in the table
+fn to_table(value: i32, ply: i32) -> i32 {
+    match value.cmp(&0) {
+        Ordering::Greater => value + ply,
+        Ordering::Less => value - ply,
+        Ordering::Equal => 0,
+    }
+}
+
+/// Inverse of `to_table`
+fn from_table(value: i32, ply: i32) -> i32 {
+    match value.cmp(&0) {
+        Ordering::Greater => value - ply,
+        Ordering::Less => value + ply,
+        Ordering::Equal => 0,
+    }
+}
+
+/// Identifies a position by its variant and Zobrist hash
+type PositionKey = (Variant, u64);
+
+fn position_key(game: &Game) -> PositionKey {
+    (*game.board().variant(), game.zobrist())
+}
+
+/// How strongly a `Computer` plays
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Plays any open tile
+    Random,
+    /// Often plays randomly, and otherwise only looks one move ahead
+    Easy,
+    /// Sometimes plays randomly, and otherwise looks two moves ahead
+    Medium,
+    /// Never plays randomly, and looks four moves ahead
+    Hard,
+    /// Never loses
+    Perfect,
+}
+
+impl Difficulty {
+    /// Chance of playing a random open tile instead of searching
+    fn blunder_chance(self) -> f64 {
+        match self {
+            Difficulty::Random => 1.0,
+            Difficulty::Easy => 0.5,
+            Difficulty::Medium => 0.2,
+            Difficulty::Hard | Difficulty::Perfect => 0.0,
+        }
+    }
+
+    /// Returns how many plies ahead a `Computer` at this difficulty searches, or
+    /// `None` if it searches to the end of the game
+    pub fn depth(self) -> Option<u8> {
+        match self {
+            Difficulty::Random => Some(0),
+            Difficulty::Easy => Some(1),
+            Difficulty::Medium => Some(2),
+            Difficulty::Hard => Some(4),
+            Difficulty::Perfect => None,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Random => "random",
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Perfect => "perfect",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDifficultyError(String);
+
+impl fmt::Display for ParseDifficultyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?} is not a difficulty; use random, easy, medium, hard or perfect",
+            self.0
+        )
+    }
+}
+
+impl error::Error for ParseDifficultyError {}
+
+impl FromStr for Difficulty {
+    type Err = ParseDifficultyError;
+
+    /// Parses the lowercase names written by `Display`, ignoring case
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::ai::*;
+    /// assert_eq!("Hard".parse(), Ok(Difficulty::Hard));
+    /// assert_eq!(Difficulty::Perfect.to_string().parse(), Ok(Difficulty::Perfect));
+    /// assert!("impossible".parse::<Difficulty>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "random" => Ok(Difficulty::Random),
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            "perfect" => Ok(Difficulty::Perfect),
+            _ => Err(ParseDifficultyError(s.to_string())),
+        }
+    }
+}
+
+/// A computer opponent playing at a given `Difficulty`
+///
+/// When several moves look equally good one is picked at random, so seeding `rng`
+/// makes a `Computer`'s games reproducible
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::ai::*;
+/// use ric_rac_roe_game::game::rng::Rng;
+/// let mut g = Game::new();
+/// let mut x = Computer::new(Difficulty::Perfect, Rng::seed_from(1));
+/// let mut o = Computer::new(Difficulty::Medium, Rng::seed_from(2));
+/// while g.result().is_none() {
+///     let coords = if *g.player_turn() == TileValue::X {
+///         x.choose_move(&g)
+///     } else {
+///         o.choose_move(&g)
+///     };
+///     g.play_coords(coords).expect("Computers only pick open tiles");
+/// }
+/// assert!(!matches!(g.result(), Some(GameResult::Winner(TileValue::O))));
+/// ```
+#[derive(Debug)]
+pub struct Computer {
+    difficulty: Difficulty,
+    engine: Engine,
+    rng: Rng,
+}
+
+impl Computer {
+    pub fn new(difficulty: Difficulty, rng: Rng) -> Self {
+        Self {
+            difficulty,
+            engine: Engine::new(),
+            rng,
+        }
+    }
+
+    pub fn difficulty(&self) -> &Difficulty {
+        &self.difficulty
+    }
+
+    /// Picks a tile for `game.player_turn()` to play
+    ///
+    /// # Panics
+    /// Panics if the game is already over
+    pub fn choose_move(&mut self, game: &Game) -> Coords {
+        let open: Vec<Coords> = game.legal_moves().collect();
+        assert!(
+            !open.is_empty(),
+            "Cannot choose a move in a game that is over"
+        );
+        if self.rng.chance(self.difficulty.blunder_chance()) {
+            return *self.rng.choose(&open).expect("There is an open tile");
+        }
+        let ranked = self.engine.rank_moves(game, self.difficulty.depth());
+        let best = ranked
+            .iter()
+            .map(|m| m.score)
+            .max()
+            .expect("There is an open tile");
+        let candidates: Vec<Coords> = ranked
+            .iter()
+            .filter(|m| m.score == best)
+            .map(|m| m.coords)
+            .collect();
+        *self
+            .rng
+            .choose(&candidates)
+            .expect("The best move is a candidate")
+    }
+}
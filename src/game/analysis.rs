@@ -0,0 +1,170 @@
+//! Explanations of a position: what every move is worth, how play would go on after
+//! it, and which lines are one tile from being completed
+use super::ai::{Engine, Score};
+use super::{Coords, Game, TileValue};
+use std::cmp::Reverse;
+use std::fmt;
+
+/// What one legal move is worth for the player making it
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveAnalysis {
+    pub coords: Coords,
+    pub score: Score,
+    /// The best replies by both sides after this move, for as far as was searched
+    pub line: Vec<Coords>,
+}
+
+/// A line that `value` could complete by playing in `coords`, the one open tile left in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Threat {
+    pub value: TileValue,
+    pub coords: Coords,
+    pub line: Vec<Coords>,
+}
+
+/// Everything `Engine::analyze` found out about a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub player_turn: TileValue,
+    /// Every legal move, best first
+    pub moves: Vec<MoveAnalysis>,
+    /// Threats for the player to move, who can win with them straight away, then
+    /// threats for the other player, which usually have to be blocked
+    pub threats: Vec<Threat>,
+}
+
+impl Analysis {
+    /// Returns the best move, or `None` if the game is over
+    pub fn best(&self) -> Option<&MoveAnalysis> {
+        self.moves.first()
+    }
+}
+
+/// Writes the moves best first, one per line, followed by the threats
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} to move", self.player_turn)?;
+        for analysis in &self.moves {
+            write!(f, "\n  {}: {}", analysis.coords, analysis.score)?;
+            if !analysis.line.is_empty() {
+                let line: Vec<String> = analysis.line.iter().map(|c| c.to_string()).collect();
+                write!(f, ", then {}", line.join(" "))?;
+            }
+        }
+        for threat in &self.threats {
+            let line: Vec<String> = threat.line.iter().map(|c| c.to_string()).collect();
+            write!(
+                f,
+                "\n{} threatens to complete {} at {}",
+                threat.value,
+                line.join(" "),
+                threat.coords
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Analyzes `game` with a new `Engine`, searching to the end of the game
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::ai::Score;
+/// use ric_rac_roe_game::game::analysis::*;
+/// let g: Game = "XX-/---/OO- x".parse().expect("is a valid position");
+/// let analysis = analyze(&g);
+/// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+///
+/// let best = analysis.best().expect("the game is not over");
+/// assert_eq!((best.coords, best.score), (c(0, 2), Score::Win(1)));
+/// assert!(best.line.is_empty());
+///
+/// let block = analysis.moves.iter().find(|m| m.coords == c(1, 1)).expect("b2 is open");
+/// assert_eq!(block.score, Score::Loss(2));
+/// assert_eq!(block.line, [c(2, 2)]);
+///
+/// assert_eq!(analysis.threats.len(), 2);
+/// assert_eq!((analysis.threats[0].value, analysis.threats[0].coords), (TileValue::X, c(0, 2)));
+/// assert_eq!((analysis.threats[1].value, analysis.threats[1].coords), (TileValue::O, c(2, 2)));
+/// ```
+pub fn analyze(game: &Game) -> Analysis {
+    Engine::new().analyze(game, None)
+}
+
+/// Finds every line `value` has all but one tile of, where the last tile is open
+pub fn threats(game: &Game, value: TileValue) -> Vec<Threat> {
+    let board = game.board();
+    board
+        .variant()
+        .win_lines()
+        .filter_map(|line| {
+            let mut open = None;
+            for coords in &line {
+                match board.value_at_coords(coords) {
+                    Some(tile) if *tile == value => {}
+                    Some(_) => return None,
+                    None if open.is_none() => open = Some(*coords),
+                    None => return None,
+                }
+            }
+            Some(Threat {
+                value,
+                coords: open?,
+                line,
+            })
+        })
+        .collect()
+}
+
+impl Engine {
+    /// Scores every legal move in `game` and finds the best line of play after each,
+    /// searching `depth` plies ahead or to the end of the game if `depth` is `None`
+    ///
+    /// As with `rank_moves`, positions beyond a limited depth count as draws
+    pub fn analyze(&mut self, game: &Game, depth: Option<u8>) -> Analysis {
+        let mut moves: Vec<MoveAnalysis> = self
+            .rank_moves(game, depth)
+            .into_iter()
+            .map(|ranked| {
+                let mut after = game.clone();
+                after
+                    .play_coords(ranked.coords)
+                    .expect("Ranked moves are legal");
+                MoveAnalysis {
+                    coords: ranked.coords,
+                    score: ranked.score,
+                    line: self.principal_line(after, depth.map(|depth| depth.saturating_sub(1))),
+                }
+            })
+            .collect();
+        moves.sort_by_key(|analysis| Reverse(analysis.score));
+        let player_turn = *game.player_turn();
+        let mut found = threats(game, player_turn);
+        found.extend(threats(game, player_turn.toggle()));
+        Analysis {
+            player_turn,
+            moves,
+            threats: found,
+        }
+    }
+
+    /// Plays the best move in `game` over and over, within `depth` plies, and returns them
+    fn principal_line(&mut self, mut game: Game, depth: Option<u8>) -> Vec<Coords> {
+        let mut line = Vec::new();
+        loop {
+            let remaining = depth.map(|depth| depth.saturating_sub(line.len() as u8));
+            let best = match remaining {
+                Some(0) => None,
+                Some(remaining) => self.best_move_to_depth(&game, remaining),
+                None => self.best_move(&game),
+            };
+            let Some(best) = best else {
+                return line;
+            };
+            game.play_coords(best.coords)
+                .expect("The engine only picks legal moves");
+            line.push(best.coords);
+        }
+    }
+}
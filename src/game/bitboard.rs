@@ -0,0 +1,117 @@
+//! Bit sets of tiles, and tables of the winning lines through every tile, which let
+//! `Board` check just the lines a move could have completed
+use super::Variant;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// A set of tiles, one bit per tile row by row from the top left
+///
+/// The classic 3x3 board fits in the low 9 bits of a single word
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Bits(Vec<u64>);
+
+impl Bits {
+    /// Creates an empty set with room for `len` tiles
+    pub(crate) fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    pub(crate) fn get(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub(crate) fn set(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    pub(crate) fn clear(&mut self, index: usize) {
+        self.0[index / 64] &= !(1 << (index % 64));
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// One winning line, stored as the index of its first tile and the distance between
+/// the indices of neighbouring tiles, so it takes the same space on any board
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Line {
+    start: usize,
+    step: usize,
+}
+
+/// The winning lines of one `Variant`, and which of them run through each tile
+pub(crate) struct LineTable {
+    lines: Vec<Line>,
+    /// Number of tiles in every line
+    length: usize,
+    /// Indices into `lines` of the lines through each tile
+    through: Vec<Vec<usize>>,
+}
+
+impl LineTable {
+    fn build(variant: Variant) -> Self {
+        let mut through = vec![Vec::new(); variant.area()];
+        let lines = variant
+            .win_lines()
+            .enumerate()
+            .map(|(line, coords)| {
+                let indices: Vec<usize> = coords.iter().map(|c| variant.index(c)).collect();
+                for &index in &indices {
+                    through[index].push(line);
+                }
+                Line {
+                    start: indices[0],
+                    // Lines only run right and downwards, so their indices go up
+                    step: indices.get(1).map_or(0, |next| next - indices[0]),
+                }
+            })
+            .collect();
+        Self {
+            lines,
+            length: variant.win_length() as usize,
+            through,
+        }
+    }
+
+    pub(crate) fn lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    /// Returns the lines through the tile at `index`
+    pub(crate) fn through(&self, index: usize) -> impl Iterator<Item = Line> + '_ {
+        self.through[index].iter().map(|&line| self.lines[line])
+    }
+
+    /// Returns the indices of the tiles in `line`
+    pub(crate) fn tiles(&self, line: Line) -> impl Iterator<Item = usize> {
+        (0..self.length).map(move |i| line.start + i * line.step)
+    }
+
+    /// Checks whether every tile of `line` is in `tiles`, or is the tile at `extra`
+    pub(crate) fn filled(&self, line: Line, tiles: &Bits, extra: Option<usize>) -> bool {
+        self.tiles(line)
+            .all(|index| Some(index) == extra || tiles.get(index))
+    }
+}
+
+impl fmt::Debug for LineTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LineTable({} lines)", self.lines.len())
+    }
+}
+
+/// Returns the line table for `variant`, building it the first time each variant is used
+pub(crate) fn line_table(variant: Variant) -> Arc<LineTable> {
+    static TABLES: OnceLock<Mutex<HashMap<Variant, Arc<LineTable>>>> = OnceLock::new();
+    let mut tables = TABLES
+        .get_or_init(Default::default)
+        .lock()
+        .expect("No thread panics while holding the lock");
+    tables
+        .entry(variant)
+        .or_insert_with(|| Arc::new(LineTable::build(variant)))
+        .clone()
+}
@@ -0,0 +1,403 @@
+//! Options `ric_rac_roe_runner` reads from its command line, and the commands a
+//! person types while playing
+use super::ai::Difficulty;
+use super::solver::Solution;
+use super::{Coords, ParseCoordsError, Variant};
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum CliError {
+    /// This option was given last, without a value
+    MissingValue(String),
+    /// This option is not one the runner knows
+    UnknownOption(String),
+    /// The value given for an option could not be used
+    InvalidValue {
+        option: String,
+        reason: String,
+    },
+    InvalidPlayer(String),
+    InvalidFormat(String),
+    /// `--variant` was given along with `--load`
+    VariantWithLoad,
+    /// More than one game was asked for with a person playing
+    SeriesWithHuman,
+    /// Both players were menace
+    TwoMenaces,
+    /// `--menace` or `--train` was given without a menace player
+    NoMenace,
+    /// A perfect player was asked to play a board too large to solve
+    TooLargeToSolve(Variant),
+    /// This command needs a file name after it
+    MissingFile(String),
+    /// A typed line that is neither a command nor a tile
+    UnknownCommand(ParseCoordsError),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingValue(option) => write!(f, "{option} needs a value"),
+            CliError::UnknownOption(option) => write!(f, "unknown option {option}"),
+            CliError::InvalidValue { option, reason } => write!(f, "invalid {option}: {reason}"),
+            CliError::InvalidPlayer(text) => write!(
+                f,
+                "{text:?} is not a player; use human, menace, random, easy, medium, hard or perfect"
+            ),
+            CliError::InvalidFormat(text) => {
+                write!(f, "{text:?} is not a format; use text, json or csv")
+            }
+            CliError::VariantWithLoad => write!(
+                f,
+                "--variant cannot be used with --load, as the saved game has its own"
+            ),
+            CliError::SeriesWithHuman => write!(
+                f,
+                "--games needs both players to be computers; people can type new to play again"
+            ),
+            CliError::TwoMenaces => write!(
+                f,
+                "only one player can be menace; use --train to let it play itself"
+            ),
+            CliError::NoMenace => write!(f, "--menace and --train need a menace player"),
+            CliError::TooLargeToSolve(variant) => write!(
+                f,
+                "perfect can only play boards of up to {} tiles, and {variant} has {}",
+                Solution::MAX_AREA,
+                variant.area()
+            ),
+            CliError::MissingFile(command) => {
+                write!(f, "{command} needs a file name, like `{command} game.txt`")
+            }
+            CliError::UnknownCommand(error) => write!(f, "{error}, or type help"),
+        }
+    }
+}
+
+impl error::Error for CliError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            CliError::UnknownCommand(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Who plays one side: a person, MENACE, or a `Computer` at some difficulty
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PlayerKind {
+    Human,
+    Menace,
+    Computer(Difficulty),
+}
+
+impl fmt::Display for PlayerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerKind::Human => write!(f, "human"),
+            PlayerKind::Menace => write!(f, "menace"),
+            PlayerKind::Computer(difficulty) => write!(f, "{difficulty}"),
+        }
+    }
+}
+
+impl FromStr for PlayerKind {
+    type Err = CliError;
+
+    /// Parses the names written by `Display`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("human") {
+            return Ok(PlayerKind::Human);
+        }
+        if s.eq_ignore_ascii_case("menace") {
+            return Ok(PlayerKind::Menace);
+        }
+        s.parse()
+            .map(PlayerKind::Computer)
+            .map_err(|_| CliError::InvalidPlayer(s.to_string()))
+    }
+}
+
+/// How the runner reports finished games
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(CliError::InvalidFormat(s.to_string())),
+        }
+    }
+}
+
+/// Everything that can be set from the command line
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub players: [PlayerKind; 2],
+    /// Index into `players` of the player who plays X, if it was given
+    pub first: Option<usize>,
+    pub variant: Variant,
+    pub games: u32,
+    pub seed: Option<u64>,
+    pub format: Format,
+    pub load: Option<String>,
+    /// File what MENACE learns is kept in
+    pub menace: Option<String>,
+    /// Games MENACE plays against itself before the real games start
+    pub train: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            players: [PlayerKind::Human; 2],
+            first: None,
+            variant: Variant::CLASSIC,
+            games: 1,
+            seed: None,
+            format: Format::Text,
+            load: None,
+            menace: None,
+            train: 0,
+        }
+    }
+}
+
+impl Options {
+    /// Reads options written as `--name value` or `--name=value`, returning `None`
+    /// if help was asked for
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::ai::Difficulty;
+    /// use ric_rac_roe_game::game::cli::*;
+    /// let parse = |args: &str| Options::parse(args.split_whitespace().map(String::from));
+    ///
+    /// let options = parse("--player1 hard --player2=menace --first 2 --variant 4x4k3 --menace m.txt")
+    ///     .unwrap()
+    ///     .expect("help was not asked for");
+    /// assert_eq!(options.x(), PlayerKind::Menace);
+    /// assert_eq!(options.o(), PlayerKind::Computer(Difficulty::Hard));
+    /// assert_eq!(options.variant.to_string(), "4x4k3");
+    /// assert_eq!(options.menace.as_deref(), Some("m.txt"));
+    /// assert!(parse("--games 5 --player1 easy --player2 random --load game.txt").is_ok());
+    /// assert!(matches!(parse("--seed 1 --help"), Ok(None)));
+    ///
+    /// assert!(matches!(parse("--load game.txt --variant 4x4k3"), Err(CliError::VariantWithLoad)));
+    /// assert!(matches!(parse("--games 2 --player1 easy"), Err(CliError::SeriesWithHuman)));
+    /// assert!(matches!(parse("--player1 menace --player2 menace"), Err(CliError::TwoMenaces)));
+    /// assert!(matches!(parse("--menace m.txt"), Err(CliError::NoMenace)));
+    /// assert!(matches!(parse("--train 10 --player1 hard"), Err(CliError::NoMenace)));
+    /// assert!(matches!(
+    ///     parse("--player2 perfect --variant 5x5k4"),
+    ///     Err(CliError::TooLargeToSolve(_))
+    /// ));
+    /// assert!(matches!(parse("--games 0"), Err(CliError::InvalidValue { .. })));
+    /// assert!(matches!(parse("--first 3"), Err(CliError::InvalidValue { .. })));
+    /// assert!(matches!(parse("--player1 grandmaster"), Err(CliError::InvalidPlayer(_))));
+    /// assert!(matches!(parse("--seed"), Err(CliError::MissingValue(_))));
+    /// assert!(matches!(parse("--colour red"), Err(CliError::UnknownOption(_))));
+    /// ```
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, CliError> {
+        let mut options = Options::default();
+        let mut variant_given = false;
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            if name == "--help" || name == "-h" {
+                return Ok(None);
+            }
+            let value = match inline_value.or_else(|| args.next()) {
+                Some(value) => value,
+                None => return Err(CliError::MissingValue(name)),
+            };
+            let invalid = |reason: &dyn fmt::Display| CliError::InvalidValue {
+                option: name.clone(),
+                reason: reason.to_string(),
+            };
+            match name.as_str() {
+                "--player1" => options.players[0] = value.parse()?,
+                "--player2" => options.players[1] = value.parse()?,
+                "--first" => {
+                    options.first = match value.as_str() {
+                        "1" => Some(0),
+                        "2" => Some(1),
+                        _ => return Err(invalid(&format!("must be 1 or 2, not {value:?}"))),
+                    }
+                }
+                "--variant" => {
+                    options.variant = value.parse().map_err(|error| invalid(&error))?;
+                    variant_given = true;
+                }
+                "--games" => {
+                    options.games = value.parse().map_err(|error| invalid(&error))?;
+                    if options.games == 0 {
+                        return Err(invalid(&"must be at least 1"));
+                    }
+                }
+                "--seed" => options.seed = Some(value.parse().map_err(|error| invalid(&error))?),
+                "--format" => options.format = value.parse()?,
+                "--load" => options.load = Some(value),
+                "--menace" => options.menace = Some(value),
+                "--train" => options.train = value.parse().map_err(|error| invalid(&error))?,
+                _ => return Err(CliError::UnknownOption(name)),
+            }
+        }
+        if variant_given && options.load.is_some() {
+            return Err(CliError::VariantWithLoad);
+        }
+        if options.games > 1 && options.players.contains(&PlayerKind::Human) {
+            return Err(CliError::SeriesWithHuman);
+        }
+        let menaces = options
+            .players
+            .iter()
+            .filter(|&&kind| kind == PlayerKind::Menace)
+            .count();
+        if menaces > 1 {
+            return Err(CliError::TwoMenaces);
+        }
+        if menaces == 0 && (options.menace.is_some() || options.train > 0) {
+            return Err(CliError::NoMenace);
+        }
+        check_players(&options.players, &options.variant)?;
+        Ok(Some(options))
+    }
+
+    /// Returns who plays X
+    pub fn x(&self) -> PlayerKind {
+        self.players[self.first.unwrap_or(0)]
+    }
+
+    /// Returns who plays O
+    pub fn o(&self) -> PlayerKind {
+        self.players[1 - self.first.unwrap_or(0)]
+    }
+}
+
+/// Returns the largest board a search `depth` plies deep answers on in well under a
+/// tenth of a second per move, or to the end of the game if `depth` is `None`
+///
+/// Every open tile is searched at every ply, so the limit shrinks quickly with depth
+fn search_tiles(depth: Option<u8>) -> usize {
+    match depth {
+        Some(0 | 1) => usize::MAX,
+        Some(2) => 20 * 20,
+        Some(_) => 9 * 9,
+        None => Solution::MAX_AREA,
+    }
+}
+
+/// Checks whether a computer playing at `difficulty` can search boards of `variant`
+/// itself, rather than playing by Monte Carlo tree search
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::ai::Difficulty;
+/// use ric_rac_roe_game::game::cli::can_search;
+/// let variant = |text: &str| text.parse().expect("is a valid variant");
+/// assert!(can_search(Difficulty::Easy, &variant("255x255k5")));
+/// assert!(can_search(Difficulty::Medium, &variant("20x20k5")));
+/// assert!(!can_search(Difficulty::Medium, &variant("21x20k5")));
+/// assert!(!can_search(Difficulty::Hard, &variant("10x10k5")));
+/// assert!(can_search(Difficulty::Perfect, &variant("4x4k3")));
+/// assert!(!can_search(Difficulty::Perfect, &variant("5x4k4")));
+/// ```
+pub fn can_search(difficulty: Difficulty, variant: &Variant) -> bool {
+    variant.area() <= search_tiles(difficulty.depth())
+}
+
+/// Checks that `players` can play on `variant`; only perfect play is refused, since
+/// it is promised never to lose, which tree search cannot keep to
+pub fn check_players(players: &[PlayerKind], variant: &Variant) -> Result<(), CliError> {
+    let perfect = PlayerKind::Computer(Difficulty::Perfect);
+    if players.contains(&perfect) && !can_search(Difficulty::Perfect, variant) {
+        return Err(CliError::TooLargeToSolve(*variant));
+    }
+    Ok(())
+}
+
+/// Something a person can type in place of a move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Play(Coords),
+    Undo,
+    Hint,
+    Review,
+    Save(String),
+    Load(String),
+    Resign,
+    New,
+    Rematch,
+    Board,
+    History,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = CliError;
+
+    /// Parses a command word, ignoring case, with a file name after `save` and
+    /// `load`; anything else is read as a tile to play
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::cli::*;
+    /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+    /// assert_eq!("b2".parse::<Command>().unwrap(), Command::Play(c(1, 1)));
+    /// assert_eq!(" 0,2 ".parse::<Command>().unwrap(), Command::Play(c(0, 2)));
+    /// assert_eq!("UNDO".parse::<Command>().unwrap(), Command::Undo);
+    /// assert_eq!("?".parse::<Command>().unwrap(), Command::Help);
+    /// assert_eq!("exit".parse::<Command>().unwrap(), Command::Quit);
+    /// assert_eq!(
+    ///     "save  my game.txt ".parse::<Command>().unwrap(),
+    ///     Command::Save("my game.txt".to_string())
+    /// );
+    /// assert!(matches!("load".parse::<Command>(), Err(CliError::MissingFile(_))));
+    /// assert!(matches!("dance".parse::<Command>(), Err(CliError::UnknownCommand(_))));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let line = s.trim();
+        let (word, argument) = match line.split_once(char::is_whitespace) {
+            Some((word, argument)) => (word, argument.trim()),
+            None => (line, ""),
+        };
+        let file = || {
+            if argument.is_empty() {
+                Err(CliError::MissingFile(word.to_string()))
+            } else {
+                Ok(argument.to_string())
+            }
+        };
+        Ok(match word.to_lowercase().as_str() {
+            "undo" => Command::Undo,
+            "hint" => Command::Hint,
+            "review" => Command::Review,
+            "save" => Command::Save(file()?),
+            "load" => Command::Load(file()?),
+            "resign" => Command::Resign,
+            "new" => Command::New,
+            "rematch" => Command::Rematch,
+            "board" => Command::Board,
+            "history" => Command::History,
+            "help" | "?" => Command::Help,
+            "quit" | "exit" => Command::Quit,
+            _ => Command::Play(line.parse().map_err(CliError::UnknownCommand)?),
+        })
+    }
+}
@@ -0,0 +1,382 @@
+//! A Monte Carlo tree search engine for boards too large to search exhaustively
+use super::player::Player;
+use super::rng::Rng;
+use super::{Coords, Game, GameResult, TileValue};
+use std::cmp::Reverse;
+use std::mem;
+use std::time::{Duration, Instant};
+
+/// How much searching `Mcts::search` does before answering
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Budget {
+    /// A fixed number of playouts, which keeps seeded searches repeatable
+    Iterations(u32),
+    /// As many playouts as fit in this much time, so results vary from run to run
+    Time(Duration),
+}
+
+/// How the rest of a game is played out from a newly added position
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Playout {
+    /// Every move is a random open tile
+    Random,
+    /// Moves complete a line when they can and otherwise block the opponent from
+    /// completing one, and are random only when neither is possible
+    Heuristic,
+}
+
+/// Search statistics for one move from the searched position
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct MoveStats {
+    pub coords: Coords,
+    /// Number of playouts that started with this move
+    pub visits: u32,
+    /// Average result of those playouts for the player making the move, counting a
+    /// win as 1, a tie as 0.5 and a loss as 0
+    pub value: f64,
+}
+
+/// The outcome of an `Mcts::search`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Search {
+    /// The most visited move
+    pub best: Coords,
+    /// Every legal move, most visited first
+    pub moves: Vec<MoveStats>,
+    /// Playouts made in this search, not counting any kept from earlier searches
+    pub iterations: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// The move that led here, or `None` at the root
+    coords: Option<Coords>,
+    /// The player who made that move
+    mover: TileValue,
+    children: Vec<usize>,
+    /// Legal moves that have no child yet
+    untried: Vec<Coords>,
+    visits: u32,
+    /// Sum of the playout results for `mover`
+    reward: f64,
+}
+
+impl Node {
+    fn new(coords: Option<Coords>, mover: TileValue, game: &Game) -> Self {
+        Self {
+            coords,
+            mover,
+            children: Vec::new(),
+            untried: game.legal_moves().collect(),
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+}
+
+/// A search tree, stored as a list of nodes with the root first
+#[derive(Debug, Clone)]
+struct Tree {
+    root: Game,
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    fn new(root: &Game) -> Self {
+        Self {
+            root: root.clone(),
+            nodes: vec![Node::new(None, root.player_turn().toggle(), root)],
+        }
+    }
+
+    /// Returns the part of this tree below `game`, if `game` continues from the
+    /// position at the root along moves the tree has already explored
+    fn reuse(mut self, game: &Game) -> Option<Self> {
+        let played = self.root.turn_history().len();
+        let history = game.turn_history();
+        if history.len() < played || history[..played] != self.root.turn_history()[..] {
+            return None;
+        }
+        let mut start = game.clone();
+        start.undo_to(played);
+        if start.board() != self.root.board() {
+            return None;
+        }
+        let mut node = 0;
+        for turn in &history[played..] {
+            node = *self.nodes[node]
+                .children
+                .iter()
+                .find(|&&child| self.nodes[child].coords == Some(*turn.coords()))?;
+        }
+        let mut nodes = Vec::new();
+        let mut stack = vec![(node, None)];
+        while let Some((old, parent)) = stack.pop() {
+            let index = nodes.len();
+            let old = &mut self.nodes[old];
+            stack.extend(
+                old.children
+                    .drain(..)
+                    .rev()
+                    .map(|child| (child, Some(index))),
+            );
+            nodes.push(Node {
+                children: Vec::new(),
+                untried: mem::take(&mut old.untried),
+                ..*old
+            });
+            if let Some(parent) = parent {
+                nodes[parent].children.push(index);
+            }
+        }
+        nodes[0].coords = None;
+        Some(Self {
+            root: game.clone(),
+            nodes,
+        })
+    }
+}
+
+/// A Monte Carlo tree search player using UCT to pick which moves to explore
+///
+/// The tree from the last search is kept, so when it is asked about a position that
+/// follows on from the last one, the playouts already made below it are reused.
+/// With an `Iterations` budget, searches are fully determined by the seed of `rng`
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::mcts::*;
+/// use ric_rac_roe_game::game::rng::Rng;
+/// let mut g: Game = "XX-/OO-/--- x".parse().expect("is a valid position");
+/// let mut mcts = Mcts::new(Budget::Iterations(2000), Playout::Random, Rng::seed_from(1));
+/// let search = mcts.search(&g);
+/// assert_eq!(search.best, Coords::build(0, 2).expect("is in bounds"));
+/// assert_eq!(search.moves.len(), 5);
+/// assert_eq!(search.moves.iter().map(|m| m.visits).sum::<u32>(), 2000);
+///
+/// let mut again = Mcts::new(Budget::Iterations(2000), Playout::Random, Rng::seed_from(1));
+/// assert_eq!(again.search(&g), search);
+///
+/// g.play_coords(Coords::build(2, 0).expect("is in bounds")).expect("is open");
+/// let search = mcts.search(&g);
+/// assert_eq!(search.best, Coords::build(1, 2).expect("is in bounds"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Mcts {
+    budget: Budget,
+    playout: Playout,
+    exploration: f64,
+    rng: Rng,
+    tree: Option<Tree>,
+}
+
+impl Mcts {
+    /// The exploration constant usually used with UCT, √2
+    pub const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+    pub fn new(budget: Budget, playout: Playout, rng: Rng) -> Self {
+        Self {
+            budget,
+            playout,
+            exploration: Self::DEFAULT_EXPLORATION,
+            rng,
+            tree: None,
+        }
+    }
+
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+
+    pub fn playout(&self) -> &Playout {
+        &self.playout
+    }
+
+    /// Sets how strongly the search favours rarely visited moves over ones that
+    /// have done well so far
+    pub fn set_exploration(&mut self, exploration: f64) {
+        self.exploration = exploration;
+    }
+
+    /// Throws away the tree kept from earlier searches
+    pub fn clear(&mut self) {
+        self.tree = None;
+    }
+
+    /// Searches `game` within the budget and returns the most visited move
+    ///
+    /// # Panics
+    /// Panics if the game is already over
+    pub fn search(&mut self, game: &Game) -> Search {
+        assert!(game.result().is_none(), "Cannot search a game that is over");
+        let mut tree = self
+            .tree
+            .take()
+            .and_then(|tree| tree.reuse(game))
+            .unwrap_or_else(|| Tree::new(game));
+        let started = Instant::now();
+        let mut iterations = 0;
+        loop {
+            let done = match self.budget {
+                Budget::Iterations(limit) => iterations >= limit,
+                Budget::Time(limit) => iterations > 0 && started.elapsed() >= limit,
+            };
+            if done {
+                break;
+            }
+            self.iterate(&mut tree);
+            iterations += 1;
+        }
+        let mut moves: Vec<MoveStats> = tree.nodes[0]
+            .children
+            .iter()
+            .map(|&child| {
+                let node = &tree.nodes[child];
+                MoveStats {
+                    coords: node.coords.expect("Only the root has no move"),
+                    visits: node.visits,
+                    value: node.reward / node.visits as f64,
+                }
+            })
+            .chain(tree.nodes[0].untried.iter().map(|&coords| MoveStats {
+                coords,
+                visits: 0,
+                value: 0.0,
+            }))
+            .collect();
+        moves.sort_by_key(|stats| Reverse(stats.visits));
+        self.tree = Some(tree);
+        Search {
+            best: moves[0].coords,
+            moves,
+            iterations,
+        }
+    }
+
+    /// Adds one playout to `tree`: walks down by UCT, adds a child for a move not
+    /// tried yet, plays the game out and backs the result up the path
+    fn iterate(&mut self, tree: &mut Tree) {
+        let mut game = tree.root.clone();
+        let mut path = vec![0];
+        let mut node = 0;
+        while tree.nodes[node].untried.is_empty() && !tree.nodes[node].children.is_empty() {
+            node = self.select(tree, node);
+            let coords = tree.nodes[node].coords.expect("Only the root has no move");
+            game.play_coords(coords)
+                .expect("Moves in the tree are legal");
+            path.push(node);
+        }
+        if !tree.nodes[node].untried.is_empty() {
+            let untried = &mut tree.nodes[node].untried;
+            let coords = untried.swap_remove(self.rng.below(untried.len()));
+            let mover = *game.player_turn();
+            game.play_coords(coords).expect("Untried moves are legal");
+            let child = tree.nodes.len();
+            tree.nodes.push(Node::new(Some(coords), mover, &game));
+            tree.nodes[node].children.push(child);
+            path.push(child);
+        }
+        let result = self.play_out(game);
+        for index in path {
+            let node = &mut tree.nodes[index];
+            node.visits += 1;
+            node.reward += match result {
+                GameResult::Winner(winner) if winner == node.mover => 1.0,
+                GameResult::Winner(_) => 0.0,
+                GameResult::Tie => 0.5,
+            };
+        }
+    }
+
+    /// Picks the child of `parent` with the highest upper confidence bound
+    fn select(&self, tree: &Tree, parent: usize) -> usize {
+        let log_visits = (tree.nodes[parent].visits as f64).ln();
+        let ucb = |child: usize| {
+            let node = &tree.nodes[child];
+            let visits = node.visits as f64;
+            node.reward / visits + self.exploration * (log_visits / visits).sqrt()
+        };
+        let children = &tree.nodes[parent].children;
+        let mut best = children[0];
+        let mut best_ucb = ucb(best);
+        for &child in &children[1..] {
+            let value = ucb(child);
+            if value > best_ucb {
+                best = child;
+                best_ucb = value;
+            }
+        }
+        best
+    }
+
+    /// Plays the game out from `game`, keeping the open tiles and the tiles that would
+    /// complete a line for each player up to date as it goes, so a playout takes time
+    /// in proportion to the size of the board rather than its square
+    fn play_out(&mut self, mut game: Game) -> GameResult {
+        let width = game.board().variant().width() as usize;
+        let tile = |coords: Coords| coords.row() as usize * width + coords.col() as usize;
+        let side = |value: TileValue| match value {
+            TileValue::X => 0,
+            TileValue::O => 1,
+        };
+        let mut open: Vec<Coords> = game.legal_moves().collect();
+        // Where each open tile is in `open`, by tile index, or `usize::MAX` once played
+        let mut slots = vec![usize::MAX; game.board().variant().area()];
+        for (slot, &coords) in open.iter().enumerate() {
+            slots[tile(coords)] = slot;
+        }
+        // Tiles that would complete a line for X and for O; some may have been played
+        // in since they were found, and are skipped
+        let mut completing: [Vec<Coords>; 2] = Default::default();
+        if self.playout == Playout::Heuristic {
+            for &coords in &open {
+                for value in [TileValue::X, TileValue::O] {
+                    if game.board().completes_line(&coords, value) {
+                        completing[side(value)].push(coords);
+                    }
+                }
+            }
+        }
+        loop {
+            if let Some(result) = *game.result() {
+                return result;
+            }
+            let mover = *game.player_turn();
+            let coords = match self.playout {
+                Playout::Random => None,
+                // Complete a line if possible, and otherwise block the opponent's
+                Playout::Heuristic => [mover, mover.toggle()].into_iter().find_map(|value| {
+                    let tiles = &mut completing[side(value)];
+                    while let Some(coords) = tiles.pop() {
+                        if slots[tile(coords)] != usize::MAX {
+                            return Some(coords);
+                        }
+                    }
+                    None
+                }),
+            };
+            let coords = coords.unwrap_or_else(|| open[self.rng.below(open.len())]);
+            let slot = slots[tile(coords)];
+            open.swap_remove(slot);
+            if let Some(&moved) = open.get(slot) {
+                slots[tile(moved)] = slot;
+            }
+            slots[tile(coords)] = usize::MAX;
+            game.play_coords(coords).expect("Open tiles are legal");
+            if self.playout == Playout::Heuristic {
+                completing[side(mover)].extend(game.board().completing_tiles(&coords));
+            }
+        }
+    }
+}
+
+impl Player for Mcts {
+    fn choose_move(&mut self, game: &Game) -> Coords {
+        self.search(game).best
+    }
+}
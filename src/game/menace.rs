@@ -0,0 +1,391 @@
+//! A player that learns from its games, after Donald Michie's matchbox machine MENACE
+use super::player::Player;
+use super::rng::Rng;
+use super::series::{Series, Tally};
+use super::symmetry::Transform;
+use super::{Board, Coords, Game, GameResult, ParseVariantError, TileValue, Variant};
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// First line of every saved `Menace`
+const HEADER: &str = "menace 1";
+
+/// Beads added for each move made in a won game
+const WIN_BEADS: u32 = 3;
+/// Beads added for each move made in a tied game
+const TIE_BEADS: u32 = 1;
+/// Beads taken away for each move made in a lost game
+const LOSS_BEADS: u32 = 1;
+
+#[derive(Debug)]
+pub enum MenaceError {
+    Io(io::Error),
+    /// The data does not start with the header `Menace::write_to` writes
+    InvalidHeader,
+    InvalidVariant {
+        line: usize,
+        error: ParseVariantError,
+    },
+    /// The line at this (1-based) line number is not a position and its beads
+    InvalidLine(usize),
+}
+
+impl fmt::Display for MenaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MenaceError::Io(error) => write!(f, "could not read or write the weights: {error}"),
+            MenaceError::InvalidHeader => write!(f, "the data is not a saved MENACE"),
+            MenaceError::InvalidVariant { line, error } => {
+                write!(f, "invalid variant on line {line}: {error}")
+            }
+            MenaceError::InvalidLine(line) => {
+                write!(f, "line {line} is not a position followed by its beads")
+            }
+        }
+    }
+}
+
+impl error::Error for MenaceError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            MenaceError::Io(error) => Some(error),
+            MenaceError::InvalidVariant { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MenaceError {
+    fn from(error: io::Error) -> Self {
+        MenaceError::Io(error)
+    }
+}
+
+/// A move `Menace` made, kept until the game ends so it can be rewarded or punished
+#[derive(Debug, Clone)]
+struct Played {
+    /// The canonical board the move was chosen on
+    state: Board,
+    /// The move, on the canonical board
+    coords: Coords,
+    mover: TileValue,
+    /// Number of turns before the move
+    ply: usize,
+}
+
+/// A player that starts out moving at random and learns which moves work
+///
+/// Like the original matchboxes, it keeps a box of beads for every position it has
+/// seen, with one colour of bead per move, and picks moves by drawing a bead at
+/// random. After each game the moves it made get extra beads if it won or tied and
+/// lose one if it lost, so good moves become more likely. Positions that are
+/// rotations or reflections of each other share a box, and so do moves that are
+/// the same by symmetry
+///
+/// It learns from every game it is told is over through `Player::game_over`, so
+/// it improves while playing people or other engines, or by playing itself with `train`
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::ai::*;
+/// use ric_rac_roe_game::game::menace::*;
+/// use ric_rac_roe_game::game::rng::Rng;
+/// let mut menace = Menace::new(Rng::seed_from(1));
+/// menace.train(Variant::CLASSIC, 2000);
+/// assert!(menace.boxes() <= 627);
+///
+/// let mut saved = Vec::new();
+/// menace.write_to(&mut saved).expect("writing to memory cannot fail");
+/// let loaded = Menace::read_from(saved.as_slice(), Rng::seed_from(2)).expect("is a saved MENACE");
+/// assert_eq!(loaded.boxes(), menace.boxes());
+///
+/// let mut beginner = Menace::new(Rng::seed_from(3));
+/// let mut perfect = Computer::new(Difficulty::Perfect, Rng::seed_from(4));
+/// let curve = learning_curve(&mut beginner, &mut perfect, &Game::new(), 4, 100);
+/// assert!(curve.iter().all(|tally| tally.games == 100 && tally.wins == 0));
+/// assert!(curve[3].losses < curve[0].losses);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Menace {
+    boxes: HashMap<Board, Vec<(Coords, u32)>>,
+    rng: Rng,
+    played: Vec<Played>,
+}
+
+impl Menace {
+    /// Makes a player that has not learned anything yet
+    pub fn new(rng: Rng) -> Self {
+        Self {
+            boxes: HashMap::new(),
+            rng,
+            played: Vec::new(),
+        }
+    }
+
+    /// Returns the number of positions it has a box of beads for
+    pub fn boxes(&self) -> usize {
+        self.boxes.len()
+    }
+
+    /// Returns the beads for each move on `board`, if it has seen the position, with
+    /// the moves on `board` itself rather than on the canonical board
+    ///
+    /// Moves that are the same by symmetry share their beads, which are listed under
+    /// just one of them
+    pub fn beads(&self, board: &Board) -> Option<Vec<(Coords, u32)>> {
+        let (state, transform) = board.canonical();
+        let back = transform.inverse();
+        let beads = self.boxes.get(&state)?;
+        Some(
+            beads
+                .iter()
+                .map(|&(coords, count)| (back.apply(coords, board.variant()), count))
+                .collect(),
+        )
+    }
+
+    /// Plays `games` games against itself on empty `variant` boards, learning from
+    /// both sides of each
+    pub fn train(&mut self, variant: Variant, games: u32) {
+        for _ in 0..games {
+            let mut game = Game::with_variant(variant);
+            while game.result().is_none() {
+                let coords = self.choose_move(&game);
+                game.play_coords(coords)
+                    .expect("Beads are only for open tiles");
+            }
+            let result = game.result().expect("The game is over");
+            self.learn(result);
+        }
+    }
+
+    /// Rewards or punishes the moves made since the last game ended, for the side
+    /// that made each one
+    fn learn(&mut self, result: GameResult) {
+        for played in self.played.drain(..) {
+            let beads = self
+                .boxes
+                .get_mut(&played.state)
+                .and_then(|beads| beads.iter_mut().find(|(c, _)| *c == played.coords))
+                .map(|(_, count)| count)
+                .expect("Every move played was drawn from a box");
+            *beads = match result {
+                GameResult::Winner(winner) if winner == played.mover => *beads + WIN_BEADS,
+                GameResult::Winner(_) => beads.saturating_sub(LOSS_BEADS),
+                GameResult::Tie => *beads + TIE_BEADS,
+            };
+        }
+    }
+
+    /// Saves every box as a line with the variant, the canonical board written like
+    /// the board in `Game::notation`, and a tile and bead count for each move
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        let mut lines: Vec<String> = self
+            .boxes
+            .iter()
+            .map(|(state, beads)| {
+                let beads: Vec<String> = beads
+                    .iter()
+                    .map(|(coords, count)| format!("{coords}:{count}"))
+                    .collect();
+                format!("{} {} {}", state.variant(), rows(state), beads.join(" "))
+            })
+            .collect();
+        lines.sort();
+        writeln!(writer, "{HEADER}")?;
+        for line in lines {
+            writeln!(writer, "{line}")?;
+        }
+        Ok(())
+    }
+
+    /// Loads the boxes saved by `write_to`
+    ///
+    /// Lines for boards that are not canonical, or with no moves or the same move
+    /// twice, are rejected as `MenaceError::InvalidLine`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::menace::*;
+    /// use ric_rac_roe_game::game::rng::Rng;
+    /// let load = |text: &str| Menace::read_from(text.as_bytes(), Rng::seed_from(1));
+    /// let menace = load("menace 1\n3x3k3 ---/---/--- a1:4 b1:4 b2:4\n").expect("is a saved MENACE");
+    /// assert_eq!(menace.boxes(), 1);
+    ///
+    /// assert!(matches!(load("menace 1\n3x3k3 ---/---/---\n"), Err(MenaceError::InvalidLine(2))));
+    /// assert!(matches!(load("menace 1\n3x3k3 ---/---/--- a1:4 a1:2\n"), Err(MenaceError::InvalidLine(2))));
+    /// assert!(matches!(load("menace 1\n3x3k3 --X/---/--- a1:4\n"), Err(MenaceError::InvalidLine(2))));
+    /// ```
+    pub fn read_from(reader: impl Read, rng: Rng) -> Result<Self, MenaceError> {
+        let mut menace = Self::new(rng);
+        let mut lines = BufReader::new(reader).lines();
+        if lines.next().transpose()?.as_deref() != Some(HEADER) {
+            return Err(MenaceError::InvalidHeader);
+        }
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let number = index + 2;
+            let invalid = || MenaceError::InvalidLine(number);
+            let mut fields = line.split_whitespace();
+            let Some(variant) = fields.next() else {
+                continue;
+            };
+            let variant: Variant =
+                variant
+                    .parse()
+                    .map_err(|error| MenaceError::InvalidVariant {
+                        line: number,
+                        error,
+                    })?;
+            let state =
+                parse_rows(fields.next().ok_or_else(invalid)?, variant).ok_or_else(invalid)?;
+            let beads = fields
+                .map(|field| {
+                    let (coords, count) = field.split_once(':')?;
+                    let coords: Coords = coords.parse().ok()?;
+                    let open =
+                        variant.contains(&coords) && state.value_at_coords(&coords).is_none();
+                    Some((coords, count.parse().ok()?)).filter(|_| open)
+                })
+                .collect::<Option<Vec<(Coords, u32)>>>()
+                .ok_or_else(invalid)?;
+            // Boxes are only ever looked up by canonical board, and drawing from one
+            // needs at least one move in it
+            let repeated = beads
+                .iter()
+                .enumerate()
+                .any(|(i, (coords, _))| beads[..i].iter().any(|(c, _)| c == coords));
+            if beads.is_empty() || repeated || state.canonical().0 != state {
+                return Err(invalid());
+            }
+            menace.boxes.insert(state, beads);
+        }
+        Ok(menace)
+    }
+}
+
+impl Player for Menace {
+    fn choose_move(&mut self, game: &Game) -> Coords {
+        let ply = game.turn_history().len();
+        // Forgets moves that were taken back, or that were made in a game that was
+        // abandoned without being reported as over
+        self.played.retain(|played| played.ply < ply);
+        let variant = *game.board().variant();
+        let (state, transform) = game.board().canonical();
+        let beads = self
+            .boxes
+            .entry(state.clone())
+            .or_insert_with(|| new_box(&state, ply));
+        let total: u32 = beads.iter().map(|(_, count)| count).sum();
+        let coords = if total == 0 {
+            // Every move here has lost so often that there are no beads left
+            beads[self.rng.below(beads.len())].0
+        } else {
+            let mut drawn = self.rng.below(total as usize) as u32;
+            beads
+                .iter()
+                .find(|(_, count)| {
+                    let found = drawn < *count;
+                    drawn = drawn.saturating_sub(*count);
+                    found
+                })
+                .expect("The drawn bead is in the box")
+                .0
+        };
+        self.played.push(Played {
+            state,
+            coords,
+            mover: *game.player_turn(),
+            ply,
+        });
+        transform.inverse().apply(coords, &variant)
+    }
+
+    fn game_over(&mut self, _game: &Game, result: &GameResult) {
+        self.learn(*result);
+    }
+}
+
+/// Fills a box for a position first seen after `ply` turns, with fewer beads for
+/// later moves as the original did, so later mistakes are unlearned faster
+fn new_box(state: &Board, ply: usize) -> Vec<(Coords, u32)> {
+    let count = 4u32.saturating_sub(ply as u32 / 2).max(1);
+    let variant = state.variant();
+    let stabilizers: Vec<Transform> = Transform::symmetries(variant)
+        .filter(|&transform| state.transformed(transform) == *state)
+        .collect();
+    state
+        .empty_tiles()
+        .filter(|&coords| {
+            stabilizers.iter().all(|transform| {
+                let image = transform.apply(coords, variant);
+                (coords.row(), coords.col()) <= (image.row(), image.col())
+            })
+        })
+        .map(|coords| (coords, count))
+        .collect()
+}
+
+fn rows(board: &Board) -> String {
+    let width = board.variant().width() as usize;
+    let tiles: Vec<char> = board
+        .iter()
+        .map(|(_, value)| match value {
+            Some(TileValue::X) => 'X',
+            Some(TileValue::O) => 'O',
+            None => '-',
+        })
+        .collect();
+    let rows: Vec<String> = tiles
+        .chunks(width)
+        .map(|row| row.iter().collect())
+        .collect();
+    rows.join("/")
+}
+
+fn parse_rows(text: &str, variant: Variant) -> Option<Board> {
+    let rows: Vec<&str> = text.split('/').collect();
+    if rows.len() != variant.height() as usize {
+        return None;
+    }
+    let mut board = Board::with_variant(variant);
+    for (row, line) in rows.iter().enumerate() {
+        if line.chars().count() != variant.width() as usize {
+            return None;
+        }
+        for (col, tile) in line.chars().enumerate() {
+            let value = match tile {
+                'X' => Some(TileValue::X),
+                'O' => Some(TileValue::O),
+                '-' => None,
+                _ => return None,
+            };
+            board.set_tile(&variant.coords(row as u8, col as u8).ok()?, &value);
+        }
+    }
+    Some(board)
+}
+
+/// Plays `blocks` rounds of `games_per_block` games between `learner` and
+/// `reference`, taking turns at playing X from `start`, and returns the learner's
+/// results for each round so its progress can be followed
+///
+/// The learner keeps learning throughout
+pub fn learning_curve(
+    learner: &mut Menace,
+    reference: &mut dyn Player,
+    start: &Game,
+    blocks: u32,
+    games_per_block: u32,
+) -> Vec<Tally> {
+    (0..blocks)
+        .map(|_| {
+            Series::play(learner, reference, start, games_per_block)
+                .stats()
+                .overall
+        })
+        .collect()
+}
@@ -0,0 +1,253 @@
+//! Sources of moves, and a `Match` that plays two of them against each other
+use super::ai::Computer;
+use super::rng::Rng;
+use super::{Coords, Game, GameResult, TileValue, Turn, TurnError};
+use std::collections::VecDeque;
+use std::io;
+
+/// Something that can choose moves in a `Game`, like a person at a keyboard or a bot
+///
+/// Only `choose_move` is required; the other methods let a player follow along
+/// with the game and do nothing by default
+pub trait Player {
+    /// Picks a tile for `game.player_turn()` to play
+    fn choose_move(&mut self, game: &Game) -> Coords;
+
+    /// Like `choose_move`, but returns `None` if no move is coming, like when a
+    /// person's input has run out, which resigns the game
+    ///
+    /// `Match` asks for moves through this, so only players that can give up need
+    /// to implement it
+    fn try_choose_move(&mut self, game: &Game) -> Option<Coords> {
+        Some(self.choose_move(game))
+    }
+
+    /// Called after the other player's `turn` has been played in `game`
+    fn opponent_moved(&mut self, _game: &Game, _turn: &Turn) {}
+
+    /// Called when a move returned by `choose_move` could not be played, just
+    /// before `choose_move` is asked again
+    fn illegal_move(&mut self, _game: &Game, _coords: Coords, _error: &TurnError) {}
+
+    /// Called once the game has ended with `result`
+    fn game_over(&mut self, _game: &Game, _result: &GameResult) {}
+}
+
+/// A person choosing moves by typing tiles on standard input
+///
+/// Once standard input is closed no more moves can be read, so `try_choose_move`
+/// returns `None` and the person resigns any `Match` they are playing
+///
+/// # Panics
+/// `choose_move` panics if standard input is closed before a move is chosen
+#[derive(Debug, Default)]
+pub struct HumanPlayer;
+
+impl HumanPlayer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reads a line from standard input, or returns `None` once it is closed
+    fn read_line(&mut self) -> Option<String> {
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(input),
+        }
+    }
+}
+
+impl Player for HumanPlayer {
+    fn choose_move(&mut self, g: &Game) -> Coords {
+        self.try_choose_move(g)
+            .expect("Standard input was closed before a move was chosen")
+    }
+
+    /// Asks for a tile in any form `Coords` can be parsed from, like `b2`
+    fn try_choose_move(&mut self, g: &Game) -> Option<Coords> {
+        println!("{}", g);
+        let variant = *g.board().variant();
+        loop {
+            println!(
+                "Player {}, input the tile you would like to play in (e.g. b2 or 1,1): ",
+                g.player_turn()
+            );
+            match self.read_line()?.parse::<Coords>() {
+                Ok(coords) if variant.contains(&coords) => return Some(coords),
+                Ok(coords) => println!("{coords} is not on the board!"),
+                Err(error) => println!("{error}"),
+            }
+        }
+    }
+
+    fn illegal_move(&mut self, _game: &Game, _coords: Coords, error: &TurnError) {
+        println!("{error}");
+    }
+}
+
+/// A bot that plays any open tile
+#[derive(Debug)]
+pub struct RandomPlayer {
+    rng: Rng,
+}
+
+impl RandomPlayer {
+    pub fn new(rng: Rng) -> Self {
+        Self { rng }
+    }
+}
+
+impl Player for RandomPlayer {
+    fn choose_move(&mut self, game: &Game) -> Coords {
+        *self
+            .rng
+            .choose(&game.legal_moves().collect::<Vec<_>>())
+            .expect("Cannot choose a move in a game that is over")
+    }
+}
+
+/// A player that makes a fixed list of moves in order, mostly useful for tests
+///
+/// Once every move in the script has been used it resigns any `Match` it is playing
+///
+/// # Panics
+/// `choose_move` panics once every move in the script has been used
+#[derive(Debug, Clone)]
+pub struct ScriptedPlayer {
+    moves: VecDeque<Coords>,
+}
+
+impl ScriptedPlayer {
+    pub fn new(moves: impl IntoIterator<Item = Coords>) -> Self {
+        Self {
+            moves: moves.into_iter().collect(),
+        }
+    }
+
+    /// Returns the moves that have not been played yet
+    pub fn remaining(&self) -> &VecDeque<Coords> {
+        &self.moves
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn choose_move(&mut self, game: &Game) -> Coords {
+        self.try_choose_move(game)
+            .expect("Scripted player ran out of moves")
+    }
+
+    fn try_choose_move(&mut self, _game: &Game) -> Option<Coords> {
+        self.moves.pop_front()
+    }
+}
+
+impl Player for Computer {
+    fn choose_move(&mut self, game: &Game) -> Coords {
+        Computer::choose_move(self, game)
+    }
+}
+
+/// Plays a `Game` to completion between two `Player`s
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::player::*;
+/// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+/// let mut x = ScriptedPlayer::new([c(0, 0), c(1, 1), c(2, 2)]);
+/// let mut o = ScriptedPlayer::new([c(0, 1), c(0, 2)]);
+/// let (g, outcome) = Match::new(&mut x, &mut o).play();
+/// assert_eq!(outcome, MatchOutcome::Finished(GameResult::Winner(TileValue::X)));
+/// assert_eq!(g.turn_history().len(), 5);
+/// ```
+pub struct Match<'a> {
+    game: Game,
+    x: &'a mut dyn Player,
+    o: &'a mut dyn Player,
+}
+
+impl<'a> Match<'a> {
+    /// Sets up a new game where `x` plays X and `o` plays O
+    pub fn new(x: &'a mut dyn Player, o: &'a mut dyn Player) -> Self {
+        Self::from_game(Game::new(), x, o)
+    }
+
+    /// Continues an existing `game` where `x` plays X and `o` plays O
+    pub fn from_game(game: Game, x: &'a mut dyn Player, o: &'a mut dyn Player) -> Self {
+        Self { game, x, o }
+    }
+
+    /// Asks each player for moves in turn until the game ends, then returns the
+    /// finished game and how it ended
+    ///
+    /// A move that cannot be played is reported back to the player with
+    /// `Player::illegal_move` and the same player is asked again
+    ///
+    /// A player whose `Player::try_choose_move` returns `None` resigns: play stops
+    /// there, leaving the game itself without a result, and both players are told
+    /// through `Player::game_over` that the other won
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::player::*;
+    /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+    /// let mut x = ScriptedPlayer::new([c(0, 0), c(1, 1)]);
+    /// let mut o = ScriptedPlayer::new([c(0, 1)]);
+    /// let (g, outcome) = Match::new(&mut x, &mut o).play();
+    /// assert_eq!(outcome, MatchOutcome::Resigned(TileValue::O));
+    /// assert_eq!(outcome.result(), GameResult::Winner(TileValue::X));
+    /// assert!(g.result().is_none());
+    /// assert_eq!(g.turn_history().len(), 3);
+    /// ```
+    pub fn play(mut self) -> (Game, MatchOutcome) {
+        let outcome = loop {
+            if let Some(result) = *self.game.result() {
+                break MatchOutcome::Finished(result);
+            }
+            let (mover, waiting) = match self.game.player_turn() {
+                TileValue::X => (&mut *self.x, &mut *self.o),
+                TileValue::O => (&mut *self.o, &mut *self.x),
+            };
+            let Some(coords) = mover.try_choose_move(&self.game) else {
+                break MatchOutcome::Resigned(*self.game.player_turn());
+            };
+            match self.game.play_coords(coords) {
+                Ok(_) => {
+                    let turn = self
+                        .game
+                        .turn_history()
+                        .last()
+                        .expect("A turn was just played");
+                    waiting.opponent_moved(&self.game, turn);
+                }
+                Err(error) => mover.illegal_move(&self.game, coords, &error),
+            }
+        };
+        let result = outcome.result();
+        self.x.game_over(&self.game, &result);
+        self.o.game_over(&self.game, &result);
+        (self.game, outcome)
+    }
+}
+
+/// How a `Match` ended
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MatchOutcome {
+    /// The game itself ended with this result
+    Finished(GameResult),
+    /// The player of this side resigned before the game ended
+    Resigned(TileValue),
+}
+
+impl MatchOutcome {
+    /// Returns the result of the match, counting a resignation as a win for the other side
+    pub fn result(&self) -> GameResult {
+        match self {
+            MatchOutcome::Finished(result) => *result,
+            MatchOutcome::Resigned(loser) => GameResult::Winner(loser.toggle()),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! A compact one-line notation for positions, like `X-O/-X-/--O x`
+//!
+//! Rows are written top to bottom and separated by `/`, with `X`, `O` or `-` for each
+//! tile, followed by the player to move as `x` or `o`. Boards whose win length is not
+//! their shorter side have it written as a third field, like `----/----/---- x 3`
+use super::{Board, Coords, Game, TileValue, Variant, VariantBuildError};
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum PositionError {
+    /// The notation does not have a board and a player to move, plus an optional win length
+    WrongFieldCount(usize),
+    InvalidTile(char),
+    /// Not every row has the same number of tiles as the first
+    RaggedRows,
+    /// The board is more than 255 tiles wide or tall
+    BoardTooLarge,
+    InvalidPlayerTurn(String),
+    InvalidWinLength(String),
+    InvalidVariant(VariantBuildError),
+    /// X always moves first, so X must have as many tiles as O when it is X's turn,
+    /// and one more when it is O's
+    WrongTileCounts {
+        x: usize,
+        o: usize,
+        player_turn: TileValue,
+    },
+    BothWon,
+    /// The winner's lines could not all have been finished by their last move, or the
+    /// loser moved after the game was over
+    PlayedAfterWin(TileValue),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PositionError::WrongFieldCount(count) => write!(
+                f,
+                "expected a board, a player to move and an optional win length, found {count} fields"
+            ),
+            PositionError::InvalidTile(tile) => {
+                write!(f, "{tile:?} is not a tile; use X, O or -")
+            }
+            PositionError::RaggedRows => write!(f, "every row must have the same number of tiles"),
+            PositionError::BoardTooLarge => {
+                write!(f, "the board can be at most 255 tiles wide and tall")
+            }
+            PositionError::InvalidPlayerTurn(text) => {
+                write!(f, "{text:?} is not a player to move; use x or o")
+            }
+            PositionError::InvalidWinLength(text) => {
+                write!(f, "{text:?} is not a win length")
+            }
+            PositionError::InvalidVariant(error) => write!(f, "invalid variant: {error}"),
+            PositionError::WrongTileCounts { x, o, player_turn } => write!(
+                f,
+                "X has {x} tiles and O has {o}, which is impossible when it is {player_turn}'s turn"
+            ),
+            PositionError::BothWon => write!(f, "X and O cannot both have won"),
+            PositionError::PlayedAfterWin(winner) => {
+                write!(f, "the game went on after {winner} had won")
+            }
+        }
+    }
+}
+
+impl error::Error for PositionError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PositionError::InvalidVariant(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Game {
+    /// Sets up a game partway through, with `board` already played on and `player_turn`
+    /// to move, checking that the position could be reached in a real game
+    ///
+    /// The returned game has no `turn_history`, since the order of the turns is unknown
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::position::PositionError;
+    /// let mut b = Board::new();
+    /// b.set_tile(&Coords::build(1, 1).expect("is in bounds"), &Some(TileValue::X));
+    /// let g = Game::from_position(b.clone(), TileValue::O).expect("is a reachable position");
+    /// assert!(g.turn_history().is_empty());
+    /// assert!(matches!(
+    ///     Game::from_position(b, TileValue::X),
+    ///     Err(PositionError::WrongTileCounts { x: 1, o: 0, player_turn: TileValue::X })
+    /// ));
+    /// ```
+    pub fn from_position(board: Board, player_turn: TileValue) -> Result<Self, PositionError> {
+        let (x, o) = (board.count(TileValue::X), board.count(TileValue::O));
+        let counts_match = match player_turn {
+            TileValue::X => x == o,
+            TileValue::O => x == o + 1,
+        };
+        if !counts_match {
+            return Err(PositionError::WrongTileCounts { x, o, player_turn });
+        }
+        let mut game = Game::with_variant(board.variant);
+        game.board = board;
+        game.player_turn = player_turn;
+        let lines_of = |value: TileValue| -> Vec<Vec<Coords>> {
+            game.board
+                .variant
+                .win_lines()
+                .filter(|line| {
+                    line.iter()
+                        .all(|coords| *game.board.value_at_coords(coords) == Some(value))
+                })
+                .collect()
+        };
+        let (x_lines, o_lines) = (lines_of(TileValue::X), lines_of(TileValue::O));
+        match (x_lines.is_empty(), o_lines.is_empty()) {
+            (false, false) => return Err(PositionError::BothWon),
+            (false, true) => check_single_win(&x_lines, TileValue::X, player_turn)?,
+            (true, false) => check_single_win(&o_lines, TileValue::O, player_turn)?,
+            (true, true) => {}
+        }
+        game.check_and_update_result();
+        Ok(game)
+    }
+
+    /// Returns a value whose `Display` writes the game's position in the notation
+    /// described in the `position` module
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// let mut g = Game::new();
+    /// g.play_coords(Coords::build(1, 1).expect("is in bounds")).expect("This tile is open and the game is not over");
+    /// assert_eq!(g.notation().to_string(), "---/-X-/--- o");
+    /// ```
+    pub fn notation(&self) -> Notation<'_> {
+        Notation(self)
+    }
+}
+
+/// Checks that every winning line of `winner` could have been completed by one final
+/// move, after which it would be the other player's turn
+fn check_single_win(
+    lines: &[Vec<Coords>],
+    winner: TileValue,
+    player_turn: TileValue,
+) -> Result<(), PositionError> {
+    let shared_tile = lines[0]
+        .iter()
+        .any(|coords| lines.iter().all(|line| line.contains(coords)));
+    if player_turn == winner || !shared_tile {
+        return Err(PositionError::PlayedAfterWin(winner));
+    }
+    Ok(())
+}
+
+/// The win length assumed when the notation leaves it out
+fn default_win_length(width: u8, height: u8) -> u8 {
+    width.min(height)
+}
+
+/// Writes a `Game`'s position in one line; see `Game::notation`
+pub struct Notation<'a>(&'a Game);
+
+impl fmt::Display for Notation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let board = self.0.board();
+        let variant = board.variant();
+        for (i, (coords, value)) in board.iter().enumerate() {
+            if i > 0 && coords.col() == 0 {
+                write!(f, "/")?;
+            }
+            match value {
+                Some(value) => write!(f, "{value}")?,
+                None => write!(f, "-")?,
+            }
+        }
+        write!(f, " {}", self.0.player_turn().to_string().to_lowercase())?;
+        if variant.win_length() != default_win_length(variant.width(), variant.height()) {
+            write!(f, " {}", variant.win_length())?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Game {
+    type Err = PositionError;
+
+    /// Parses a position written in the notation described in the `position` module
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::position::PositionError;
+    /// let g: Game = "X-O/-X-/--O x".parse().expect("is a valid position");
+    /// assert_eq!(*g.board().value_at_coords(&Coords::build(0, 2).expect("is in bounds")), Some(TileValue::O));
+    /// assert_eq!(*g.player_turn(), TileValue::X);
+    /// assert_eq!(g.notation().to_string(), "X-O/-X-/--O x");
+    ///
+    /// let g: Game = "XXX/OO-/--- o".parse().expect("is a valid position");
+    /// assert!(matches!(g.result(), Some(GameResult::Winner(TileValue::X))));
+    ///
+    /// let g: Game = "----/----/---- x 3".parse().expect("is a valid position");
+    /// assert_eq!(g.board().variant().win_length(), 3);
+    ///
+    /// assert!(matches!("XX-/---/--- o".parse::<Game>(), Err(PositionError::WrongTileCounts { .. })));
+    /// assert!(matches!("XXX/OOO/X-- o".parse::<Game>(), Err(PositionError::BothWon)));
+    /// assert!(matches!("XXX/OO-/O-- x".parse::<Game>(), Err(PositionError::PlayedAfterWin(TileValue::X))));
+    /// assert!(matches!("X?-/---/--- o".parse::<Game>(), Err(PositionError::InvalidTile('?'))));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if !(2..=3).contains(&fields.len()) {
+            return Err(PositionError::WrongFieldCount(fields.len()));
+        }
+        let rows = fields[0]
+            .split('/')
+            .map(|row| {
+                row.chars()
+                    .map(|tile| match tile {
+                        'X' => Ok(Some(TileValue::X)),
+                        'O' => Ok(Some(TileValue::O)),
+                        '-' => Ok(None),
+                        _ => Err(PositionError::InvalidTile(tile)),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let width = rows[0].len();
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(PositionError::RaggedRows);
+        }
+        let (width, height) = match (u8::try_from(width), u8::try_from(rows.len())) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err(PositionError::BoardTooLarge),
+        };
+        let player_turn = match fields[1] {
+            "x" | "X" => TileValue::X,
+            "o" | "O" => TileValue::O,
+            other => return Err(PositionError::InvalidPlayerTurn(other.to_string())),
+        };
+        let win_length = match fields.get(2) {
+            Some(field) => field
+                .parse()
+                .map_err(|_| PositionError::InvalidWinLength(field.to_string()))?,
+            None => default_win_length(width, height),
+        };
+        let variant =
+            Variant::build(width, height, win_length).map_err(PositionError::InvalidVariant)?;
+        let mut board = Board::with_variant(variant);
+        for (coords, value) in variant.all_coords().zip(rows.into_iter().flatten()) {
+            board.set_tile(&coords, &value);
+        }
+        Game::from_position(board, player_turn)
+    }
+}
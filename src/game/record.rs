@@ -0,0 +1,325 @@
+//! A PGN-like format for storing whole games
+//!
+//! A record starts with tags like `[X "Alice"]`, one per line, followed by a blank
+//! line and the numbered move list, ending with the result:
+//!
+//! ```text
+//! [X "Alice"]
+//! [O "Bob"]
+//! [Variant "3x3k3"]
+//! [Result "1-0"]
+//!
+//! 1. b2 a1 2. c3 a3 3. a2 c1 {c1 was needed to block} 4. c2 1-0
+//! ```
+//!
+//! Tiles are written as a column letter followed by a row number, with `a1` in the
+//! top left. The result is `1-0` when X wins, `0-1` when O wins, `1/2-1/2` for a tie
+//! and `*` for an unfinished game. Text in braces is a comment on the move before it
+use super::{Coords, Game, GameResult, ParseVariantError, TileValue, TurnError, Variant};
+use std::collections::BTreeMap;
+use std::error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum RecordError {
+    /// The line starting with `[` at this (1-based) line number is not a valid tag
+    InvalidTag(usize),
+    InvalidVariant(ParseVariantError),
+    /// The move at this ply (counting from 1) is not written as a tile
+    InvalidMove {
+        ply: usize,
+        text: String,
+    },
+    /// The move at this ply (counting from 1) could not be played
+    IllegalMove {
+        ply: usize,
+        coords: Coords,
+        error: TurnError,
+    },
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::InvalidTag(line) => {
+                write!(f, "line {line} is not a tag like [Name \"Value\"]")
+            }
+            RecordError::InvalidVariant(error) => write!(f, "invalid Variant tag: {error}"),
+            RecordError::InvalidMove { ply, text } => {
+                write!(f, "move {ply} ({text:?}) is not a tile like b2")
+            }
+            RecordError::IllegalMove { ply, coords, error } => {
+                write!(f, "move {ply} ({coords}) cannot be played: {error}")
+            }
+        }
+    }
+}
+
+impl error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            RecordError::InvalidTag(_) | RecordError::InvalidMove { .. } => None,
+            RecordError::InvalidVariant(error) => Some(error),
+            RecordError::IllegalMove { error, .. } => Some(error),
+        }
+    }
+}
+
+/// A finished or unfinished game along with tags describing it, such as who played
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::record::*;
+/// let mut g = Game::new();
+/// for c in [(1, 1), (0, 0), (2, 2), (0, 2), (0, 1)] {
+///     g.play_coords(Coords::build(c.0, c.1).expect("is in bounds")).expect("This tile is open and the game is not over");
+/// }
+/// let mut record = Record::new(g);
+/// record.set_tag("X", "Alice");
+/// let text = record.to_string();
+/// assert!(text.ends_with("1. b2 a1 2. c3 c1 3. b1 *\n"));
+///
+/// let loaded: Record = text.parse().expect("is a valid record");
+/// assert_eq!(loaded.tag("X"), Some("Alice"));
+/// assert_eq!(loaded.game().turn_history(), record.game().turn_history());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Record {
+    tags: Vec<(String, String)>,
+    game: Game,
+    /// Comments keyed by the number of turns played before them
+    comments: BTreeMap<usize, String>,
+}
+
+impl Record {
+    /// Makes a record of `game`, tagged with its variant and result
+    pub fn new(game: Game) -> Self {
+        let mut record = Self {
+            tags: Vec::new(),
+            game,
+            comments: BTreeMap::new(),
+        };
+        record.set_tag("Variant", &record.game.board().variant().to_string());
+        record.set_tag("Result", result_text(*record.game.result()));
+        record
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Returns the tags in the order they are written
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Returns the value of the tag called `name`, if there is one
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Sets the tag called `name` to `value`, replacing any previous value
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Returns the comment after turn `ply` (counting from 1), or before the first
+    /// turn if `ply` is 0
+    pub fn comment(&self, ply: usize) -> Option<&str> {
+        self.comments.get(&ply).map(String::as_str)
+    }
+
+    /// Sets the comment after turn `ply` (counting from 1), or before the first turn
+    /// if `ply` is 0, replacing any previous comment there
+    ///
+    /// Closing braces and line breaks cannot be written inside a comment, so they are
+    /// replaced with `)` and spaces
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::record::*;
+    /// let mut g = Game::new();
+    /// g.play_coords(Coords::build(1, 1).expect("is in bounds")).expect("is open");
+    /// g.play_coords(Coords::build(0, 1).expect("is in bounds")).expect("is open");
+    /// let mut record = Record::new(g);
+    /// record.set_comment(2, "an edge {weak}");
+    /// let text = record.to_string();
+    /// assert!(text.ends_with("1. b2 b1 {an edge {weak)} *\n"));
+    ///
+    /// let loaded: Record = text.parse().expect("is a valid record");
+    /// assert_eq!(loaded.comment(2), Some("an edge {weak)"));
+    /// assert_eq!(loaded.comment(1), None);
+    /// ```
+    pub fn set_comment(&mut self, ply: usize, text: &str) {
+        let text = text.replace('}', ")").replace(['\n', '\r'], " ");
+        self.comments.insert(ply, text);
+    }
+
+    /// Returns every comment along with the turn it follows
+    pub fn comments(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
+        self.comments
+            .iter()
+            .map(|(&ply, text)| (ply, text.as_str()))
+    }
+
+    /// Parses every record in `text`, where each new record starts with its tags
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::record::*;
+    /// let text = "[Result \"*\"]\n\n1. b2 *\n\n[Result \"*\"]\n\n1. a1 b1 *\n";
+    /// let records = Record::parse_all(text).expect("are valid records");
+    /// assert_eq!(records.len(), 2);
+    /// assert_eq!(records[1].game().turn_history().len(), 2);
+    /// ```
+    pub fn parse_all(text: &str) -> Result<Vec<Record>, RecordError> {
+        let mut records = Vec::new();
+        let mut current = String::new();
+        let mut in_moves = false;
+        for line in text.lines() {
+            let is_tag = line.trim_start().starts_with('[');
+            if is_tag && in_moves {
+                records.push(current.parse()?);
+                current.clear();
+                in_moves = false;
+            }
+            in_moves |= !is_tag && !line.trim().is_empty();
+            current.push_str(line);
+            current.push('\n');
+        }
+        if !current.trim().is_empty() {
+            records.push(current.parse()?);
+        }
+        Ok(records)
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+        if let Some(text) = self.comment(0) {
+            write!(f, "{{{text}}} ")?;
+        }
+        for (ply, turn) in self.game.turn_history().iter().enumerate() {
+            if ply % 2 == 0 {
+                write!(f, "{}. ", ply / 2 + 1)?;
+            }
+            write!(f, "{} ", turn.coords())?;
+            if let Some(text) = self.comment(ply + 1) {
+                write!(f, "{{{text}}} ")?;
+            }
+        }
+        // A Result tag can record an ending the moves alone don't show, like a resignation
+        let result = self
+            .tag("Result")
+            .unwrap_or(result_text(*self.game.result()));
+        writeln!(f, "{result}")
+    }
+}
+
+impl FromStr for Record {
+    type Err = RecordError;
+
+    /// Parses a single record, replaying its moves from an empty board
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::record::*;
+    /// let record: Record = "[Variant \"4x4k3\"]\n\n1. a1 b1 2. a2 b2 3. a3 1-0".parse().expect("is a valid record");
+    /// assert!(matches!(record.game().result(), Some(GameResult::Winner(TileValue::X))));
+    ///
+    /// let illegal = "1. a1 b2 2. a1 *".parse::<Record>();
+    /// assert!(matches!(illegal, Err(RecordError::IllegalMove { ply: 3, error: TurnError::TileFull { value: TileValue::X, .. }, .. })));
+    /// assert_eq!(illegal.expect_err("a1 is taken").to_string(), "move 3 (a1) cannot be played: X is already in a1");
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tags = Vec::new();
+        let mut lines = s.lines().enumerate().peekable();
+        while let Some((number, line)) = lines.next_if(|(_, line)| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with('[')
+        }) {
+            let line = line.trim();
+            if !line.is_empty() {
+                tags.push(parse_tag(line).ok_or(RecordError::InvalidTag(number + 1))?);
+            }
+        }
+        let variant = match tags.iter().find(|(name, _)| name == "Variant") {
+            Some((_, value)) => value.parse().map_err(RecordError::InvalidVariant)?,
+            None => Variant::CLASSIC,
+        };
+        let mut game = Game::with_variant(variant);
+        let mut comments = BTreeMap::new();
+        let moves: Vec<&str> = lines.map(|(_, line)| line).collect();
+        let moves = moves.join("\n");
+        let mut rest = moves.trim_start();
+        while !rest.is_empty() {
+            if let Some(comment) = rest.strip_prefix('{') {
+                let (text, after) = comment.split_once('}').unwrap_or((comment, ""));
+                comments.insert(game.turn_history().len(), text.trim().to_string());
+                rest = after.trim_start();
+                continue;
+            }
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '{')
+                .unwrap_or(rest.len());
+            let token = &rest[..end];
+            rest = rest[end..].trim_start();
+            if ["1-0", "0-1", "1/2-1/2", "*"].contains(&token) {
+                break;
+            }
+            let text = token.rsplit('.').next().unwrap_or_default();
+            if text.is_empty() {
+                continue;
+            }
+            let ply = game.turn_history().len() + 1;
+            let coords = text.parse().map_err(|_| RecordError::InvalidMove {
+                ply,
+                text: text.to_string(),
+            })?;
+            game.play_coords(coords)
+                .map_err(|error| RecordError::IllegalMove { ply, coords, error })?;
+        }
+        Ok(Self {
+            tags,
+            game,
+            comments,
+        })
+    }
+}
+
+fn result_text(result: Option<GameResult>) -> &'static str {
+    match result {
+        Some(GameResult::Winner(TileValue::X)) => "1-0",
+        Some(GameResult::Winner(TileValue::O)) => "0-1",
+        Some(GameResult::Tie) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+/// Parses a `[Name "Value"]` line
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(' ')?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(if c == '\\' { chars.next()? } else { c });
+    }
+    Some((name.to_string(), unescaped))
+}
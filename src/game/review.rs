@@ -0,0 +1,177 @@
+//! Reviews of finished games that compare every turn with perfect play
+use super::ai::{BestMove, Engine, Score};
+use super::record::Record;
+use super::{Game, Turn};
+use std::fmt;
+
+/// Whether a position is won, drawn or lost, ignoring how long it takes
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Outcome {
+    Loss,
+    Draw,
+    Win,
+}
+
+impl From<Score> for Outcome {
+    fn from(score: Score) -> Self {
+        match score {
+            Score::Win(_) => Outcome::Win,
+            Score::Draw => Outcome::Draw,
+            Score::Loss(_) => Outcome::Loss,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Win => write!(f, "win"),
+            Outcome::Draw => write!(f, "draw"),
+            Outcome::Loss => write!(f, "loss"),
+        }
+    }
+}
+
+/// How one turn compared with the best move available
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnReview {
+    /// The number of this turn, counting from 1
+    pub ply: usize,
+    pub turn: Turn,
+    /// What the move played was worth for the player who made it
+    pub score: Score,
+    /// A move with the best score available, which may be the move played
+    pub best: BestMove,
+}
+
+impl TurnReview {
+    /// Returns `true` if the move played threw away a better outcome, like turning a
+    /// drawn position into a lost one
+    pub fn is_blunder(&self) -> bool {
+        Outcome::from(self.score) < Outcome::from(self.best.score)
+    }
+
+    /// Describes a blunder and what should have been played instead, or returns
+    /// `None` for any other move
+    pub fn annotation(&self) -> Option<String> {
+        if !self.is_blunder() {
+            return None;
+        }
+        Some(format!(
+            "blunder: turned a {} into a {}; {} keeps the {}",
+            Outcome::from(self.best.score),
+            Outcome::from(self.score),
+            self.best.coords,
+            Outcome::from(self.best.score)
+        ))
+    }
+}
+
+/// Every turn of a game checked against perfect play
+#[derive(Debug, Clone, PartialEq)]
+pub struct Review {
+    pub turns: Vec<TurnReview>,
+}
+
+impl Review {
+    pub fn blunders(&self) -> impl Iterator<Item = &TurnReview> {
+        self.turns.iter().filter(|turn| turn.is_blunder())
+    }
+
+    /// Adds the annotation of every blunder to `record` as a comment after it
+    pub fn annotate(&self, record: &mut Record) {
+        for turn in &self.turns {
+            if let Some(annotation) = turn.annotation() {
+                record.set_comment(turn.ply, &annotation);
+            }
+        }
+    }
+}
+
+/// Writes one line per turn, with blunders marked
+impl fmt::Display for Review {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for turn in &self.turns {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+            write!(
+                f,
+                "{}. {} {}: {}",
+                turn.ply,
+                turn.turn.value(),
+                turn.turn.coords(),
+                turn.score
+            )?;
+            if let Some(annotation) = turn.annotation() {
+                write!(f, " ({annotation})")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reviews `game` with a new `Engine`, searching every position to the end
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::ai::Score;
+/// use ric_rac_roe_game::game::record::Record;
+/// use ric_rac_roe_game::game::review::*;
+/// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+/// let mut g = Game::new();
+/// for coords in [c(1, 1), c(0, 1), c(0, 0), c(2, 2), c(2, 0), c(1, 0), c(0, 2)] {
+///     g.play_coords(coords).expect("is open");
+/// }
+/// let review = review(&g);
+/// assert_eq!(review.turns.len(), 7);
+///
+/// let blunders: Vec<_> = review.blunders().collect();
+/// assert_eq!(blunders.len(), 1);
+/// assert_eq!(blunders[0].ply, 2);
+/// assert_eq!(blunders[0].score, Score::Loss(6));
+/// assert!(matches!(Outcome::from(blunders[0].best.score), Outcome::Draw));
+///
+/// let mut record = Record::new(g);
+/// review.annotate(&mut record);
+/// assert!(record.comment(2).expect("the blunder is annotated").starts_with("blunder: turned a draw into a loss"));
+/// ```
+pub fn review(game: &Game) -> Review {
+    Engine::new().review(game, None)
+}
+
+impl Engine {
+    /// Replays `game` from the position before its first turn, comparing every turn
+    /// with the best move, searching `depth` plies ahead or to the end of the game
+    /// if `depth` is `None`
+    pub fn review(&mut self, game: &Game, depth: Option<u8>) -> Review {
+        let mut replay = game.clone();
+        replay.undo_to(0);
+        let mut turns = Vec::new();
+        for (index, turn) in game.turn_history().iter().enumerate() {
+            let ranked = self.rank_moves(&replay, depth);
+            let score = ranked
+                .iter()
+                .find(|ranked| ranked.coords == *turn.coords())
+                .expect("Every turn in the history was a legal move")
+                .score;
+            let best = *ranked
+                .iter()
+                .max_by_key(|ranked| ranked.score)
+                .expect("There was a legal move");
+            turns.push(TurnReview {
+                ply: index + 1,
+                turn: turn.clone(),
+                score,
+                best,
+            });
+            replay
+                .take_turn(turn.clone())
+                .expect("Every turn in the history was legal");
+        }
+        Review { turns }
+    }
+}
@@ -0,0 +1,131 @@
+//! `Deserialize` implementations for the types whose fields depend on each other, which
+//! check the data the same way the rest of the crate would have built it, and
+//! `Serialize` for `Board`, which is saved as a list of tiles rather than as bits
+use super::{Board, Game, GameResult, TileValue, Turn, Variant};
+use serde::de::Error;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Deserialize)]
+struct VariantFields {
+    width: u8,
+    height: u8,
+    win_length: u8,
+}
+
+impl<'de> Deserialize<'de> for Variant {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = VariantFields::deserialize(deserializer)?;
+        Variant::build(fields.width, fields.height, fields.win_length)
+            .map_err(|error| D::Error::custom(format!("invalid variant: {error}")))
+    }
+}
+
+#[derive(Deserialize)]
+struct BoardFields {
+    variant: Variant,
+    tiles: Vec<Option<TileValue>>,
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = BoardFields::deserialize(deserializer)?;
+        if fields.tiles.len() != fields.variant.area() {
+            return Err(D::Error::custom(format!(
+                "expected {} tiles for a {} board, found {}",
+                fields.variant.area(),
+                fields.variant,
+                fields.tiles.len()
+            )));
+        }
+        let mut board = Board::with_variant(fields.variant);
+        for (coords, value) in fields.variant.all_coords().zip(&fields.tiles) {
+            board.set_tile(&coords, value);
+        }
+        Ok(board)
+    }
+}
+
+/// Writes the tiles row by row from the top left
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tiles: Vec<Option<TileValue>> = self.iter().map(|(_, value)| value).collect();
+        let mut state = serializer.serialize_struct("Board", 2)?;
+        state.serialize_field("variant", &self.variant)?;
+        state.serialize_field("tiles", &tiles)?;
+        state.end()
+    }
+}
+
+#[derive(Deserialize)]
+struct GameFields {
+    board: Board,
+    turn_history: Vec<Turn>,
+    player_turn: TileValue,
+    result: Option<GameResult>,
+    #[serde(default)]
+    free_placement: bool,
+}
+
+/// Rebuilds the game by replaying `turn_history`, so a save whose board, turn or result
+/// disagree with its history is rejected
+///
+/// Turns that had been undone are not saved, so the loaded game has nothing to redo.
+/// Games in free placement mode may start from any position, but otherwise the
+/// position before the first turn must be one `Game::from_position` accepts
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// let mut g = Game::new();
+/// g.play_coords(Coords::build(1, 1).expect("is in bounds")).expect("This tile is open and the game is not over");
+/// let json = serde_json::to_string(&g).expect("Games can be serialized");
+/// let loaded: Game = serde_json::from_str(&json).expect("The save is untouched");
+/// assert_eq!(loaded.notation().to_string(), g.notation().to_string());
+///
+/// let tampered = json.replace(r#""player_turn":"O""#, r#""player_turn":"X""#);
+/// assert!(serde_json::from_str::<Game>(&tampered).is_err());
+/// ```
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let fields = GameFields::deserialize(deserializer)?;
+        let mut start = fields.board.clone();
+        for turn in &fields.turn_history {
+            if !start.variant.contains(&turn.coords)
+                || *start.value_at_coords(&turn.coords) != Some(turn.value)
+            {
+                return Err(D::Error::custom(format!(
+                    "turn {turn:?} does not match the board"
+                )));
+            }
+            start.set_tile(&turn.coords, &None);
+        }
+        let first_turn = fields
+            .turn_history
+            .first()
+            .map_or(fields.player_turn, |turn| turn.value);
+        let mut game = if fields.free_placement {
+            let mut game = Game::with_variant(start.variant);
+            game.board = start;
+            game.player_turn = first_turn;
+            game.check_and_update_result();
+            game
+        } else {
+            Game::from_position(start, first_turn)
+                .map_err(|error| D::Error::custom(format!("invalid starting position: {error}")))?
+        };
+        game.set_free_placement(fields.free_placement);
+        for turn in fields.turn_history {
+            let description = format!("{turn:?}");
+            game.take_turn(turn).map_err(|error| {
+                D::Error::custom(format!("turn {description} is illegal: {error}"))
+            })?;
+        }
+        if game.player_turn != fields.player_turn || game.result != fields.result {
+            return Err(D::Error::custom(
+                "the player to move or the result does not match the turns played",
+            ));
+        }
+        Ok(game)
+    }
+}
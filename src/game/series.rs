@@ -0,0 +1,221 @@
+//! Series of games between the same two players, with statistics about how they went
+use super::player::{Match, MatchOutcome, Player};
+use super::{Coords, Game, GameResult, TileValue};
+use std::fmt;
+
+/// One of the two players in a `Series`, who take turns playing X
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seat {
+    One,
+    Two,
+}
+
+impl Seat {
+    pub fn other(self) -> Self {
+        match self {
+            Seat::One => Seat::Two,
+            Seat::Two => Seat::One,
+        }
+    }
+}
+
+/// A finished game from a `Series`, how it ended, and which player played X in it
+#[derive(Debug, Clone)]
+pub struct SeriesGame {
+    pub game: Game,
+    pub outcome: MatchOutcome,
+    pub x: Seat,
+}
+
+impl SeriesGame {
+    /// Returns the side `seat` played
+    pub fn side_of(&self, seat: Seat) -> TileValue {
+        if seat == self.x {
+            TileValue::X
+        } else {
+            TileValue::O
+        }
+    }
+
+    /// Returns the first move the players made, after any moves in the starting position
+    pub fn first_move(&self, start: &Game) -> Option<Coords> {
+        let played = start.turn_history().len();
+        self.game
+            .turn_history()
+            .get(played)
+            .map(|turn| *turn.coords())
+    }
+}
+
+/// Games played between two players, who swap sides after every game so that each
+/// plays X, and so moves first, in half of them
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::ai::*;
+/// use ric_rac_roe_game::game::rng::Rng;
+/// use ric_rac_roe_game::game::series::*;
+/// let mut perfect = Computer::new(Difficulty::Perfect, Rng::seed_from(1));
+/// let mut random = Computer::new(Difficulty::Random, Rng::seed_from(2));
+/// let series = Series::play(&mut perfect, &mut random, &Game::new(), 10);
+/// assert_eq!(series.games().len(), 10);
+/// assert_eq!(series.games()[1].x, Seat::Two);
+///
+/// let stats = series.stats();
+/// assert_eq!(stats.overall.games, 10);
+/// assert_eq!(stats.overall.losses, 0);
+/// assert_eq!(stats.as_x.games, 5);
+/// assert_eq!(stats.overall.reversed().wins, 0);
+/// let openings: u32 = stats.first_moves.iter().map(|(_, tally)| tally.games).sum();
+/// assert_eq!(openings, 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Series {
+    start: Game,
+    games: Vec<SeriesGame>,
+}
+
+impl Series {
+    /// Plays `games` games, each continuing from `start`, with `one` playing X in the
+    /// first game and `two` in the second
+    pub fn play(one: &mut dyn Player, two: &mut dyn Player, start: &Game, games: u32) -> Self {
+        let mut series = Self {
+            start: start.clone(),
+            games: Vec::new(),
+        };
+        let mut x = Seat::One;
+        for _ in 0..games {
+            let (game, outcome) = match x {
+                Seat::One => Match::from_game(start.clone(), one, two).play(),
+                Seat::Two => Match::from_game(start.clone(), two, one).play(),
+            };
+            series.games.push(SeriesGame { game, outcome, x });
+            x = x.other();
+        }
+        series
+    }
+
+    /// Returns the position every game started from
+    pub fn start(&self) -> &Game {
+        &self.start
+    }
+
+    pub fn games(&self) -> &[SeriesGame] {
+        &self.games
+    }
+
+    pub fn stats(&self) -> SeriesStats {
+        let mut stats = SeriesStats::default();
+        for game in &self.games {
+            let one = game.side_of(Seat::One);
+            stats.overall.add(game, one);
+            match one {
+                TileValue::X => stats.as_x.add(game, one),
+                TileValue::O => stats.as_o.add(game, one),
+            }
+            if let Some(coords) = game.first_move(&self.start) {
+                let opener = *self.start.player_turn();
+                match stats.first_moves.iter_mut().find(|(c, _)| *c == coords) {
+                    Some((_, tally)) => tally.add(game, opener),
+                    None => {
+                        let mut tally = Tally::default();
+                        tally.add(game, opener);
+                        stats.first_moves.push((coords, tally));
+                    }
+                }
+            }
+        }
+        stats
+            .first_moves
+            .sort_by_key(|(coords, _)| (coords.row(), coords.col()));
+        stats
+    }
+}
+
+/// Wins, draws and losses from one player's point of view, along with how long the
+/// games were
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Tally {
+    pub games: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Total number of turns taken in all the games
+    pub plies: u32,
+}
+
+impl Tally {
+    /// Counts a finished `game` for the player of `side`
+    pub fn add(&mut self, game: &SeriesGame, side: TileValue) {
+        self.games += 1;
+        self.plies += game.game.turn_history().len() as u32;
+        match game.outcome.result() {
+            GameResult::Winner(winner) if winner == side => self.wins += 1,
+            GameResult::Winner(_) => self.losses += 1,
+            GameResult::Tie => self.draws += 1,
+        }
+    }
+
+    /// Returns the same games from the opponent's point of view
+    pub fn reversed(self) -> Self {
+        Self {
+            wins: self.losses,
+            losses: self.wins,
+            ..self
+        }
+    }
+
+    /// Returns the mean number of turns per game, or 0 if there were no games
+    pub fn average_length(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            self.plies as f64 / self.games as f64
+        }
+    }
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} wins, {} draws and {} losses in {} games, {:.1} moves long on average",
+            self.wins,
+            self.draws,
+            self.losses,
+            self.games,
+            self.average_length()
+        )
+    }
+}
+
+/// How a `Series` went, from player one's point of view unless noted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SeriesStats {
+    pub overall: Tally,
+    pub as_x: Tally,
+    pub as_o: Tally,
+    /// Results for whichever player made each first move, from the top left
+    pub first_moves: Vec<(Coords, Tally)>,
+}
+
+/// Writes a summary over several lines
+impl fmt::Display for SeriesStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Player one: {}", self.overall)?;
+        writeln!(f, "  as X: {}", self.as_x)?;
+        writeln!(f, "  as O: {}", self.as_o)?;
+        writeln!(f, "Player two: {}", self.overall.reversed())?;
+        writeln!(f, "  as X: {}", self.as_o.reversed())?;
+        writeln!(f, "  as O: {}", self.as_x.reversed())?;
+        write!(f, "By first move, for the player who made it:")?;
+        for (coords, tally) in &self.first_moves {
+            write!(f, "\n  {coords}: {tally}")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,347 @@
+//! A table of the game-theoretic value of every position reachable in a variant,
+//! for instant perfect play on small boards
+use super::ai::{BestMove, Score};
+use super::{Board, Coords, Game, GameResult, TileValue, Variant, VariantBuildError};
+use std::error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// Bytes every saved `Solution` starts with
+const MAGIC: &[u8; 4] = b"RRRS";
+const FORMAT_VERSION: u8 = 1;
+
+/// Each table byte keeps the kind of score in its top two bits and the number of
+/// plies in the rest; a zero byte marks a board that cannot be reached
+const UNREACHABLE: u8 = 0;
+const WIN: u8 = 1 << 6;
+const DRAW: u8 = 2 << 6;
+const LOSS: u8 = 3 << 6;
+const PLIES: u8 = (1 << 6) - 1;
+
+#[derive(Debug)]
+pub enum SolutionError {
+    /// The table for boards with more tiles than `Solution::MAX_AREA` would not fit in memory
+    BoardTooLarge(Variant),
+    Io(io::Error),
+    /// The data does not start with the header `Solution::write_to` writes
+    InvalidHeader,
+    InvalidVariant(VariantBuildError),
+    /// The table is not one byte for every possible board
+    WrongLength {
+        expected: usize,
+        found: usize,
+    },
+    /// A table byte that no score is stored as
+    InvalidValue(u8),
+}
+
+impl fmt::Display for SolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolutionError::BoardTooLarge(variant) => write!(
+                f,
+                "a {variant} board has {} tiles, but at most {} can be solved",
+                variant.area(),
+                Solution::MAX_AREA
+            ),
+            SolutionError::Io(error) => write!(f, "could not read or write the solution: {error}"),
+            SolutionError::InvalidHeader => write!(f, "the data is not a saved solution"),
+            SolutionError::InvalidVariant(error) => write!(f, "invalid variant: {error}"),
+            SolutionError::WrongLength { expected, found } => {
+                write!(f, "expected {expected} table entries, found {found}")
+            }
+            SolutionError::InvalidValue(byte) => write!(f, "{byte:#04x} is not a stored score"),
+        }
+    }
+}
+
+impl error::Error for SolutionError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SolutionError::Io(error) => Some(error),
+            SolutionError::InvalidVariant(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SolutionError {
+    fn from(error: io::Error) -> Self {
+        SolutionError::Io(error)
+    }
+}
+
+/// How many positions of each kind a `Solution` holds
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Counts {
+    pub reachable: usize,
+    pub x_wins: usize,
+    pub o_wins: usize,
+    pub ties: usize,
+    /// Reachable positions after each number of turns, starting from the empty board
+    pub by_ply: Vec<usize>,
+}
+
+impl Counts {
+    /// Returns the number of finished positions with `result`
+    pub fn terminal(&self, result: GameResult) -> usize {
+        match result {
+            GameResult::Winner(TileValue::X) => self.x_wins,
+            GameResult::Winner(TileValue::O) => self.o_wins,
+            GameResult::Tie => self.ties,
+        }
+    }
+}
+
+/// The value of every position reachable from `Game::with_variant`, assuming perfect
+/// play from both sides
+///
+/// Positions are looked up by their board alone, since the player to move follows
+/// from the number of tiles each side has. Boards are numbered by reading their tiles
+/// as base-3 digits, so the table has one byte for each of the 3^area possible
+/// boards and every lookup is a single index
+///
+/// # Examples
+/// ```rust
+/// use ric_rac_roe_game::game::*;
+/// use ric_rac_roe_game::game::ai::Score;
+/// use ric_rac_roe_game::game::solver::*;
+/// let solution = Solution::solve(Variant::CLASSIC).expect("3x3 is small enough");
+/// assert_eq!(solution.score(&Game::new()), Some(Score::Draw));
+///
+/// let counts = solution.counts();
+/// assert_eq!(counts.reachable, 5478);
+/// assert_eq!(counts.terminal(GameResult::Winner(TileValue::X)), 626);
+/// assert_eq!(counts.terminal(GameResult::Winner(TileValue::O)), 316);
+/// assert_eq!(counts.terminal(GameResult::Tie), 16);
+/// assert_eq!(counts.by_ply, [1, 9, 72, 252, 756, 1260, 1520, 1140, 390, 78]);
+///
+/// let g: Game = "X-O/-X-/--- o".parse().expect("is a valid position");
+/// assert_eq!(solution.score(&g), Some(Score::Draw));
+/// let best = solution.best_move(&g).expect("the game is not over");
+/// assert_eq!(best.coords, Coords::build(2, 2).expect("is in bounds"));
+///
+/// let mut saved = Vec::new();
+/// solution.write_to(&mut saved).expect("writing to memory cannot fail");
+/// let loaded = Solution::read_from(saved.as_slice()).expect("is a saved solution");
+/// assert_eq!(loaded.counts(), counts);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    variant: Variant,
+    table: Vec<u8>,
+}
+
+impl Solution {
+    /// Largest board that can be solved, which needs a 43 MB table
+    pub const MAX_AREA: usize = 16;
+
+    /// Searches every position reachable in `variant`
+    ///
+    /// Every board up to `MAX_AREA` tiles fits in the table, but only small boards
+    /// like 3x3 solve quickly
+    pub fn solve(variant: Variant) -> Result<Self, SolutionError> {
+        if variant.area() > Self::MAX_AREA {
+            return Err(SolutionError::BoardTooLarge(variant));
+        }
+        let mut solution = Self {
+            variant,
+            table: vec![UNREACHABLE; 3usize.pow(variant.area() as u32)],
+        };
+        solution.search(&mut Game::with_variant(variant), 0);
+        Ok(solution)
+    }
+
+    pub fn variant(&self) -> &Variant {
+        &self.variant
+    }
+
+    /// Returns the value of `game` for the player to move, or `None` if it is from
+    /// another variant or could not have been reached by playing from the start
+    pub fn score(&self, game: &Game) -> Option<Score> {
+        self.lookup(game.board())
+    }
+
+    /// Like `score`, for the player whose turn it would be on `board`
+    pub fn lookup(&self, board: &Board) -> Option<Score> {
+        if *board.variant() != self.variant {
+            return None;
+        }
+        decode(self.table[self.index(board)])
+    }
+
+    /// Returns a move that keeps the best score for the player to move, and that score,
+    /// or `None` if the game is over or not in the table
+    pub fn best_move(&self, game: &Game) -> Option<BestMove> {
+        let mut best: Option<BestMove> = None;
+        for coords in game.legal_moves() {
+            let mut board = game.board().clone();
+            board.set_tile(&coords, &Some(*game.player_turn()));
+            let score = step_back(self.lookup(&board)?);
+            if best.is_none_or(|best| score > best.score) {
+                best = Some(BestMove { coords, score });
+            }
+        }
+        best
+    }
+
+    /// Counts the reachable positions by how they ended and how many turns in they are
+    ///
+    /// These come from the results `Game` itself worked out while solving, so they can
+    /// be compared with published figures to check them
+    pub fn counts(&self) -> Counts {
+        let area = self.variant.area();
+        let mut counts = Counts {
+            by_ply: vec![0; area + 1],
+            ..Counts::default()
+        };
+        for (mut index, &byte) in self.table.iter().enumerate() {
+            if byte == UNREACHABLE {
+                continue;
+            }
+            let (mut x, mut o) = (0, 0);
+            while index > 0 {
+                match index % 3 {
+                    1 => x += 1,
+                    2 => o += 1,
+                    _ => {}
+                }
+                index /= 3;
+            }
+            counts.reachable += 1;
+            counts.by_ply[x + o] += 1;
+            match decode(byte) {
+                // Whoever is to move has already lost, so the other side made the last move
+                Some(Score::Loss(0)) if x > o => counts.x_wins += 1,
+                Some(Score::Loss(0)) => counts.o_wins += 1,
+                Some(Score::Draw) if x + o == area => counts.ties += 1,
+                _ => {}
+            }
+        }
+        while counts.by_ply.last() == Some(&0) {
+            counts.by_ply.pop();
+        }
+        counts
+    }
+
+    /// Saves the solution as a short header followed by the table, one byte per board
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[
+            FORMAT_VERSION,
+            self.variant.width(),
+            self.variant.height(),
+            self.variant.win_length(),
+        ])?;
+        writer.write_all(&self.table)
+    }
+
+    /// Loads a solution saved by `write_to`
+    pub fn read_from(mut reader: impl Read) -> Result<Self, SolutionError> {
+        let mut header = [0; 8];
+        reader.read_exact(&mut header)?;
+        if header[..4] != *MAGIC || header[4] != FORMAT_VERSION {
+            return Err(SolutionError::InvalidHeader);
+        }
+        let variant = Variant::build(header[5], header[6], header[7])
+            .map_err(SolutionError::InvalidVariant)?;
+        if variant.area() > Self::MAX_AREA {
+            return Err(SolutionError::BoardTooLarge(variant));
+        }
+        let mut table = Vec::new();
+        reader.read_to_end(&mut table)?;
+        let expected = 3usize.pow(variant.area() as u32);
+        if table.len() != expected {
+            return Err(SolutionError::WrongLength {
+                expected,
+                found: table.len(),
+            });
+        }
+        if let Some(&byte) = table
+            .iter()
+            .find(|&&byte| byte != UNREACHABLE && decode(byte).is_none())
+        {
+            return Err(SolutionError::InvalidValue(byte));
+        }
+        Ok(Self { variant, table })
+    }
+
+    /// Numbers `board` by reading its tiles from the top left as base-3 digits, with
+    /// the first tile least significant
+    fn index(&self, board: &Board) -> usize {
+        let mut place = 1;
+        let mut index = 0;
+        for (_, value) in board.iter() {
+            index += place
+                * match value {
+                    None => 0,
+                    Some(TileValue::X) => 1,
+                    Some(TileValue::O) => 2,
+                };
+            place *= 3;
+        }
+        index
+    }
+
+    /// Fills in the table for `game`, whose board has number `index`, and everything
+    /// reachable from it
+    fn search(&mut self, game: &mut Game, index: usize) -> Score {
+        if let Some(score) = decode(self.table[index]) {
+            return score;
+        }
+        let score = match game.result() {
+            Some(GameResult::Winner(_)) => Score::Loss(0),
+            Some(GameResult::Tie) => Score::Draw,
+            None => {
+                let digit = match game.player_turn() {
+                    TileValue::X => 1,
+                    TileValue::O => 2,
+                };
+                let moves: Vec<Coords> = game.legal_moves().collect();
+                let mut best = None;
+                for coords in moves {
+                    let tile = coords.row() as usize * self.variant.width() as usize
+                        + coords.col() as usize;
+                    game.play_coords(coords).expect("Legal moves can be played");
+                    let child = self.search(game, index + digit * 3usize.pow(tile as u32));
+                    game.undo();
+                    let score = step_back(child);
+                    if best.is_none_or(|best| score > best) {
+                        best = Some(score);
+                    }
+                }
+                best.expect("A game that is not over has a legal move")
+            }
+        };
+        self.table[index] = encode(score);
+        score
+    }
+}
+
+/// Turns the score of the position after a move into the score of the move for
+/// the player who made it
+fn step_back(score: Score) -> Score {
+    match score {
+        Score::Win(plies) => Score::Loss(plies + 1),
+        Score::Draw => Score::Draw,
+        Score::Loss(plies) => Score::Win(plies + 1),
+    }
+}
+
+fn encode(score: Score) -> u8 {
+    match score {
+        Score::Win(plies) => WIN | plies,
+        Score::Draw => DRAW,
+        Score::Loss(plies) => LOSS | plies,
+    }
+}
+
+fn decode(byte: u8) -> Option<Score> {
+    let plies = byte & PLIES;
+    match byte & !PLIES {
+        WIN if plies > 0 => Some(Score::Win(plies)),
+        DRAW if plies == 0 => Some(Score::Draw),
+        LOSS => Some(Score::Loss(plies)),
+        _ => None,
+    }
+}
@@ -0,0 +1,142 @@
+//! Rotations and reflections of the board, which never change who is winning
+//!
+//! Square boards have eight symmetries and other rectangles have four. Mapping
+//! positions to a canonical representative lets tables store one entry for all of a
+//! position's symmetric copies
+use super::{Board, Coords, Variant};
+
+/// A rotation or reflection of the board
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    /// A quarter turn clockwise
+    Rotate90,
+    Rotate180,
+    /// A quarter turn anticlockwise
+    Rotate270,
+    /// Mirrors left and right
+    FlipHorizontal,
+    /// Mirrors top and bottom
+    FlipVertical,
+    /// Mirrors along the diagonal from the top left, swapping rows and columns
+    Transpose,
+    /// Mirrors along the diagonal from the top right
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::Rotate90,
+        Transform::Rotate180,
+        Transform::Rotate270,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    /// Checks whether the transform maps `variant`'s board onto itself, which only
+    /// quarter turns and diagonal reflections of non-square boards don't
+    pub fn preserves(&self, variant: &Variant) -> bool {
+        variant.width() == variant.height()
+            || matches!(
+                self,
+                Transform::Identity
+                    | Transform::Rotate180
+                    | Transform::FlipHorizontal
+                    | Transform::FlipVertical
+            )
+    }
+
+    /// Returns the transforms that map `variant`'s board onto itself
+    pub fn symmetries(variant: &Variant) -> impl Iterator<Item = Transform> + '_ {
+        Self::ALL
+            .into_iter()
+            .filter(move |transform| transform.preserves(variant))
+    }
+
+    /// Returns the transform that undoes this one
+    pub fn inverse(&self) -> Transform {
+        match self {
+            Transform::Rotate90 => Transform::Rotate270,
+            Transform::Rotate270 => Transform::Rotate90,
+            other => *other,
+        }
+    }
+
+    /// Moves `coords` to where this transform takes it on `variant`'s board
+    ///
+    /// # Panics
+    /// Panics if the transform does not preserve `variant`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// use ric_rac_roe_game::game::symmetry::Transform;
+    /// let v = Variant::CLASSIC;
+    /// let top_middle = Coords::build(0, 1).expect("is in bounds");
+    /// let right_middle = Transform::Rotate90.apply(top_middle, &v);
+    /// assert_eq!(right_middle, Coords::build(1, 2).expect("is in bounds"));
+    /// assert_eq!(Transform::Rotate90.inverse().apply(right_middle, &v), top_middle);
+    /// ```
+    pub fn apply(&self, coords: Coords, variant: &Variant) -> Coords {
+        assert!(
+            self.preserves(variant),
+            "{self:?} does not map a {variant} board onto itself"
+        );
+        let (row, col) = (coords.0, coords.1);
+        let (last_row, last_col) = (variant.height() - 1, variant.width() - 1);
+        match self {
+            Transform::Identity => Coords(row, col),
+            Transform::Rotate90 => Coords(col, last_row - row),
+            Transform::Rotate180 => Coords(last_row - row, last_col - col),
+            Transform::Rotate270 => Coords(last_col - col, row),
+            Transform::FlipHorizontal => Coords(row, last_col - col),
+            Transform::FlipVertical => Coords(last_row - row, col),
+            Transform::Transpose => Coords(col, row),
+            Transform::AntiTranspose => Coords(last_col - col, last_row - row),
+        }
+    }
+}
+
+impl Board {
+    /// Returns a copy of the board with every tile moved by `transform`
+    ///
+    /// # Panics
+    /// Panics if the transform does not preserve the board's variant
+    pub fn transformed(&self, transform: Transform) -> Board {
+        let mut board = Board::with_variant(self.variant);
+        for (coords, value) in self.iter() {
+            board.set_tile(&transform.apply(coords, &self.variant), &value);
+        }
+        board
+    }
+
+    /// Returns the canonical representative of the board's symmetric copies, along with
+    /// the transform that takes this board to it
+    ///
+    /// Boards that are rotations or reflections of each other have the same canonical
+    /// board. Coordinates on this board can be mapped onto the canonical board with
+    /// `transform.apply`, and back with `transform.inverse().apply`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// let mut top_right = Board::new();
+    /// top_right.set_tile(&Coords::build(0, 2).expect("is in bounds"), &Some(TileValue::X));
+    /// let mut bottom_left = Board::new();
+    /// bottom_left.set_tile(&Coords::build(2, 0).expect("is in bounds"), &Some(TileValue::X));
+    /// let (a, to_a) = top_right.canonical();
+    /// let (b, _) = bottom_left.canonical();
+    /// assert!(a.iter().eq(b.iter()));
+    /// let corner = to_a.apply(Coords::build(0, 2).expect("is in bounds"), a.variant());
+    /// assert_eq!(*a.value_at_coords(&corner), Some(TileValue::X));
+    /// ```
+    pub fn canonical(&self) -> (Board, Transform) {
+        Transform::symmetries(&self.variant)
+            .map(|transform| (self.transformed(transform), transform))
+            .min_by(|(a, _), (b, _)| (&a.x, &a.o).cmp(&(&b.x, &b.o)))
+            .expect("The identity is always a symmetry")
+    }
+}
@@ -0,0 +1,21 @@
+//! Random keys for Zobrist hashing, where a position's hash is the XOR of a key for
+//! each occupied tile, so it can be updated with one XOR whenever a tile changes
+use super::rng::Rng;
+use super::TileValue;
+
+const SEED: u64 = 0x05EE_D0F7_1C7A_C70E;
+
+/// Returns the key for `value` sitting in the tile at `index`, counting row by row
+/// from the top left
+pub(crate) fn tile_key(index: usize, value: TileValue) -> u64 {
+    let side = match value {
+        TileValue::X => 0,
+        TileValue::O => 1,
+    };
+    Rng::seed_from(SEED ^ (index as u64 * 2 + side)).next_u64()
+}
+
+/// Returns the key mixed in when it is O's turn
+pub(crate) fn o_to_move_key() -> u64 {
+    Rng::seed_from(SEED ^ u64::MAX).next_u64()
+}
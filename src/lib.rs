@@ -1,46 +1,699 @@
 pub mod game {
+    use ai::ParseDifficultyError;
+    use bitboard::{Bits, LineTable};
+    use cli::CliError;
+    use menace::MenaceError;
+    use position::PositionError;
+    use record::RecordError;
+    use solver::SolutionError;
+    use std::error;
     use std::fmt;
+    use std::hash::{Hash, Hasher};
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    pub mod ai;
+    pub mod analysis;
+    mod bitboard;
+    pub mod cli;
+    pub mod mcts;
+    pub mod menace;
+    pub mod player;
+    pub mod position;
+    pub mod record;
+    pub mod review;
+    pub mod rng;
+    pub mod series;
+    pub mod solver;
+    #[cfg(feature = "serde")]
+    mod serialization;
+    pub mod symmetry;
+    mod zobrist;
 
     /// Represents board coordinates `(row, col)`
-    #[derive(Debug, Copy, Clone, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
     pub struct Coords(u8, u8);
     #[derive(Debug)]
     pub enum CoordsBuildError {
-        OutOfBounds,
+        /// `(row, col)` is not on a board `width` tiles wide and `height` tiles tall
+        OutOfBounds {
+            row: u8,
+            col: u8,
+            width: u8,
+            height: u8,
+        },
+    }
+
+    impl fmt::Display for CoordsBuildError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                CoordsBuildError::OutOfBounds {
+                    row,
+                    col,
+                    width,
+                    height,
+                } => write!(
+                    f,
+                    "({row}, {col}) is not on the board: rows go from 0 to {} and columns from 0 to {}",
+                    height - 1,
+                    width - 1
+                ),
+            }
+        }
     }
+
+    impl error::Error for CoordsBuildError {}
     impl Coords {
+        /// Builds coordinates on the classic 3x3 board; use `Variant::coords` for
+        /// coordinates on other boards
         pub fn build(row: u8, col: u8) -> Result<Self, CoordsBuildError> {
-            let bounds = 0..3;
-            if !(bounds.contains(&row) && bounds.contains(&col)) {
-                return Err(CoordsBuildError::OutOfBounds);
+            Variant::CLASSIC.coords(row, col)
+        }
+
+        /// Makes coordinates without checking them against any board
+        pub const fn new(row: u8, col: u8) -> Self {
+            Self(row, col)
+        }
+
+        pub fn row(&self) -> u8 {
+            self.0
+        }
+
+        pub fn col(&self) -> u8 {
+            self.1
+        }
+    }
+
+    /// Writes the coordinates in algebraic notation: column letters followed by a
+    /// 1-based row number, with `a1` in the top left
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// assert_eq!(Coords::build(1, 2).expect("is in bounds").to_string(), "c2");
+    /// assert_eq!(Coords::new(9, 27).to_string(), "ab10");
+    /// ```
+    impl fmt::Display for Coords {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let mut letters = Vec::new();
+            let mut col = self.1 as u32 + 1;
+            while col > 0 {
+                col -= 1;
+                letters.push(char::from_u32('a' as u32 + col % 26).expect("is a lowercase letter"));
+                col /= 26;
             }
-            Ok(Self(row, col))
+            let letters: String = letters.iter().rev().collect();
+            write!(f, "{letters}{}", self.0 as u32 + 1)
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct ParseCoordsError(String);
+
+    impl fmt::Display for ParseCoordsError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f,
+                "{:?} is not a tile; use a column letter and row number like b2, a row and column like 1,1, or a numpad digit",
+                self.0
+            )
+        }
+    }
+
+    impl error::Error for ParseCoordsError {}
+
+    impl FromStr for Coords {
+        type Err = ParseCoordsError;
+
+        /// Parses coordinates in any of these forms:
+        /// - algebraic notation, as written by `Display`, like `b2`
+        /// - a 0-based row and column separated by a comma, like `1,1`
+        /// - a single digit laid out like a numeric keypad on the classic board, so `7`
+        ///   is the top left, `5` the center and `3` the bottom right
+        ///
+        /// The coordinates are not checked against any board
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let center = Coords::build(1, 1).expect("is in bounds");
+        /// assert_eq!("b2".parse::<Coords>().expect("is valid"), center);
+        /// assert_eq!("B2".parse::<Coords>().expect("is valid"), center);
+        /// assert_eq!("1, 1".parse::<Coords>().expect("is valid"), center);
+        /// assert_eq!("5".parse::<Coords>().expect("is valid"), center);
+        /// assert_eq!("7".parse::<Coords>().expect("is valid"), Coords::build(0, 0).expect("is in bounds"));
+        /// assert_eq!("3".parse::<Coords>().expect("is valid"), Coords::build(2, 2).expect("is in bounds"));
+        /// assert!("0".parse::<Coords>().is_err());
+        /// assert!("b0".parse::<Coords>().is_err());
+        /// assert!("mwlqkwz1".parse::<Coords>().is_err());
+        /// ```
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let text = s.trim().to_ascii_lowercase();
+            let error = || ParseCoordsError(s.to_string());
+            if let Some((row, col)) = text.split_once(',') {
+                let row = row.trim().parse().map_err(|_| error())?;
+                let col = col.trim().parse().map_err(|_| error())?;
+                return Ok(Coords(row, col));
+            }
+            if let Ok(digit @ 1..=9) = text.parse::<u8>() {
+                return Ok(Coords(2 - (digit - 1) / 3, (digit - 1) % 3));
+            }
+            let split = text
+                .find(|c: char| !c.is_ascii_lowercase())
+                .ok_or_else(error)?;
+            let (letters, number) = text.split_at(split);
+            if letters.is_empty() {
+                return Err(error());
+            }
+            let col = letters
+                .bytes()
+                .try_fold(0u32, |col, letter| {
+                    col.checked_mul(26)?.checked_add((letter - b'a') as u32 + 1)
+                })
+                .ok_or_else(error)?
+                - 1;
+            let row = number
+                .parse::<u32>()
+                .ok()
+                .and_then(|row| row.checked_sub(1))
+                .ok_or_else(error)?;
+            match (u8::try_from(row), u8::try_from(col)) {
+                (Ok(row), Ok(col)) => Ok(Coords(row, col)),
+                _ => Err(error()),
+            }
+        }
+    }
+
+    /// The size of a board and how many tiles in a row it takes to win on it,
+    /// e.g. 3x3 with 3 in a row for classic Tic-Tac-Toe or 15x15 with 5 for Gomoku
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct Variant {
+        width: u8,
+        height: u8,
+        win_length: u8,
+    }
+    #[derive(Debug)]
+    pub enum VariantBuildError {
+        EmptyBoard,
+        /// The win length is 0 or longer than the board is wide or tall, so nobody could win
+        InvalidWinLength,
+    }
+
+    impl fmt::Display for VariantBuildError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                VariantBuildError::EmptyBoard => {
+                    write!(f, "the board must be at least one tile wide and tall")
+                }
+                VariantBuildError::InvalidWinLength => write!(
+                    f,
+                    "the win length must be between 1 and the length of the board's longest side"
+                ),
+            }
+        }
+    }
+
+    impl error::Error for VariantBuildError {}
+    impl Variant {
+        /// Classic Tic-Tac-Toe: a 3x3 board with 3 in a row to win
+        pub const CLASSIC: Variant = Variant {
+            width: 3,
+            height: 3,
+            win_length: 3,
+        };
+
+        /// Builds a variant with a `width` by `height` board, won by getting
+        /// `win_length` tiles in a row horizontally, vertically or diagonally
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let gomoku = Variant::build(15, 15, 5).expect("is a valid variant");
+        /// assert_eq!(gomoku.width(), 15);
+        /// assert!(matches!(Variant::build(0, 3, 3), Err(VariantBuildError::EmptyBoard)));
+        /// assert!(matches!(Variant::build(3, 3, 4), Err(VariantBuildError::InvalidWinLength)));
+        /// ```
+        pub fn build(width: u8, height: u8, win_length: u8) -> Result<Self, VariantBuildError> {
+            if width == 0 || height == 0 {
+                return Err(VariantBuildError::EmptyBoard);
+            }
+            if win_length == 0 || win_length > width.max(height) {
+                return Err(VariantBuildError::InvalidWinLength);
+            }
+            Ok(Self {
+                width,
+                height,
+                win_length,
+            })
+        }
+
+        pub fn width(&self) -> u8 {
+            self.width
+        }
+
+        pub fn height(&self) -> u8 {
+            self.height
+        }
+
+        pub fn win_length(&self) -> u8 {
+            self.win_length
+        }
+
+        /// Returns the number of tiles on the board
+        pub fn area(&self) -> usize {
+            self.width as usize * self.height as usize
+        }
+
+        /// Checks whether `coords` is on the board
+        pub fn contains(&self, coords: &Coords) -> bool {
+            coords.0 < self.height && coords.1 < self.width
+        }
+
+        /// Builds coordinates, checking that they are on this variant's board
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let v = Variant::build(4, 2, 2).expect("is a valid variant");
+        /// assert!(v.coords(1, 3).is_ok());
+        /// assert!(matches!(v.coords(2, 0), Err(CoordsBuildError::OutOfBounds { row: 2, col: 0, .. })));
+        /// assert_eq!(
+        ///     v.coords(2, 0).expect_err("is out of bounds").to_string(),
+        ///     "(2, 0) is not on the board: rows go from 0 to 1 and columns from 0 to 3"
+        /// );
+        /// ```
+        pub fn coords(&self, row: u8, col: u8) -> Result<Coords, CoordsBuildError> {
+            let coords = Coords(row, col);
+            if !self.contains(&coords) {
+                return Err(CoordsBuildError::OutOfBounds {
+                    row,
+                    col,
+                    width: self.width,
+                    height: self.height,
+                });
+            }
+            Ok(coords)
+        }
+
+        /// Iterates over every tile on the board, row by row from the top left
+        pub fn all_coords(&self) -> impl Iterator<Item = Coords> {
+            let width = self.width;
+            (0..self.height).flat_map(move |row| (0..width).map(move |col| Coords(row, col)))
+        }
+
+        /// Computes every line of `win_length` tiles that wins the game when filled by
+        /// one player, going right, down, and diagonally down in either direction
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// assert_eq!(Variant::CLASSIC.win_lines().count(), 8);
+        /// assert!(Variant::CLASSIC.win_lines().eq(Game::WIN_LINES.iter().map(|line| line.to_vec())));
+        /// assert_eq!(Variant::build(4, 4, 3).expect("is a valid variant").win_lines().count(), 24);
+        /// ```
+        pub fn win_lines(&self) -> impl Iterator<Item = Vec<Coords>> {
+            const DIRECTIONS: [(i16, i16); 4] = [(0, 1), (1, 0), (1, 1), (1, -1)];
+            let variant = *self;
+            let length = self.win_length as i16;
+            DIRECTIONS.into_iter().flat_map(move |(d_row, d_col)| {
+                variant.all_coords().filter_map(move |start| {
+                    let end = (
+                        start.0 as i16 + d_row * (length - 1),
+                        start.1 as i16 + d_col * (length - 1),
+                    );
+                    if !(0..variant.height as i16).contains(&end.0)
+                        || !(0..variant.width as i16).contains(&end.1)
+                    {
+                        return None;
+                    }
+                    Some(
+                        (0..length)
+                            .map(|i| {
+                                Coords(
+                                    (start.0 as i16 + d_row * i) as u8,
+                                    (start.1 as i16 + d_col * i) as u8,
+                                )
+                            })
+                            .collect(),
+                    )
+                })
+            })
+        }
+
+        fn index(&self, coords: &Coords) -> usize {
+            coords.0 as usize * self.width as usize + coords.1 as usize
+        }
+    }
+
+    impl Default for Variant {
+        fn default() -> Self {
+            Self::CLASSIC
+        }
+    }
+
+    /// Writes the variant as `{width}x{height}k{win_length}`, e.g. `15x15k5`
+    impl fmt::Display for Variant {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}x{}k{}", self.width, self.height, self.win_length)
         }
     }
 
     #[derive(Debug)]
-    pub struct Board([[Option<TileValue>; 3]; 3]);
+    pub enum ParseVariantError {
+        /// The text is not of the form `{width}x{height}k{win_length}`
+        Syntax(String),
+        Invalid(VariantBuildError),
+    }
+
+    impl fmt::Display for ParseVariantError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ParseVariantError::Syntax(text) => write!(
+                    f,
+                    "{text:?} is not a variant like 3x3k3 (width x height k win length)"
+                ),
+                ParseVariantError::Invalid(error) => write!(f, "invalid variant: {error}"),
+            }
+        }
+    }
+
+    impl error::Error for ParseVariantError {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            match self {
+                ParseVariantError::Syntax(_) => None,
+                ParseVariantError::Invalid(error) => Some(error),
+            }
+        }
+    }
+
+    impl FromStr for Variant {
+        type Err = ParseVariantError;
+
+        /// Parses a variant in the form written by its `Display` implementation
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let v: Variant = "4x3k3".parse().expect("is a valid variant");
+        /// assert_eq!((v.width(), v.height(), v.win_length()), (4, 3, 3));
+        /// assert_eq!(v.to_string(), "4x3k3");
+        /// assert!(matches!("4x3".parse::<Variant>(), Err(ParseVariantError::Syntax(_))));
+        /// assert!(matches!("3x3k4".parse::<Variant>(), Err(ParseVariantError::Invalid(_))));
+        /// ```
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let syntax_error = || ParseVariantError::Syntax(s.to_string());
+            let (width, rest) = s.split_once('x').ok_or_else(syntax_error)?;
+            let (height, win_length) = rest.split_once('k').ok_or_else(syntax_error)?;
+            let [width, height, win_length] = [width, height, win_length]
+                .map(|number| number.parse::<u8>().map_err(|_| syntax_error()));
+            Variant::build(width?, height?, win_length?).map_err(ParseVariantError::Invalid)
+        }
+    }
+
+    /// Two boards are equal when they have the same variant and the same tiles
+    #[derive(Debug, Clone)]
+    pub struct Board {
+        variant: Variant,
+        /// Tiles held by X
+        x: Bits,
+        /// Tiles held by O
+        o: Bits,
+        lines: Arc<LineTable>,
+        /// Zobrist hash of the tiles, updated by `set_tile`
+        hash: u64,
+    }
+
+    impl PartialEq for Board {
+        fn eq(&self, other: &Self) -> bool {
+            self.variant == other.variant && self.x == other.x && self.o == other.o
+        }
+    }
+
+    impl Eq for Board {}
+
+    impl Hash for Board {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.variant.hash(state);
+            state.write_u64(self.hash);
+        }
+    }
 
     impl Board {
+        /// Creates an empty classic 3x3 board
         pub fn new() -> Self {
-            Self([[None; 3]; 3])
+            Self::with_variant(Variant::CLASSIC)
+        }
+
+        /// Creates an empty board shaped like `variant`
+        pub fn with_variant(variant: Variant) -> Self {
+            Self {
+                variant,
+                x: Bits::new(variant.area()),
+                o: Bits::new(variant.area()),
+                lines: bitboard::line_table(variant),
+                hash: 0,
+            }
         }
 
+        /// Returns the Zobrist hash of the tiles, which only depends on which tiles
+        /// hold what and not on the order they were played in
+        pub fn zobrist(&self) -> u64 {
+            self.hash
+        }
+
+        pub fn variant(&self) -> &Variant {
+            &self.variant
+        }
+
+        /// # Panics
+        /// Panics if `coords` is not on the board
         pub fn value_at_coords(&self, coords: &Coords) -> &Option<TileValue> {
-            &self.0[coords.0 as usize][coords.1 as usize]
+            assert!(
+                self.variant.contains(coords),
+                "{coords:?} is not on the board"
+            );
+            let index = self.variant.index(coords);
+            if self.x.get(index) {
+                &Some(TileValue::X)
+            } else if self.o.get(index) {
+                &Some(TileValue::O)
+            } else {
+                &None
+            }
+        }
+
+        /// Returns the player who has filled a winning line, if either has
+        ///
+        /// This checks every line on the board; after a move, `winner_through` only
+        /// needs to check the lines through the tile played
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut b = Board::new();
+        /// for col in 0..3 {
+        ///     assert!(b.winner().is_none());
+        ///     b.set_tile(&Coords::build(1, col).expect("is in bounds"), &Some(TileValue::O));
+        /// }
+        /// assert_eq!(b.winner(), Some(TileValue::O));
+        /// ```
+        pub fn winner(&self) -> Option<TileValue> {
+            self.lines.lines().iter().find_map(|&line| {
+                if self.lines.filled(line, &self.x, None) {
+                    Some(TileValue::X)
+                } else if self.lines.filled(line, &self.o, None) {
+                    Some(TileValue::O)
+                } else {
+                    None
+                }
+            })
+        }
+
+        /// Returns the player holding `coords` if they have filled a winning line
+        /// through it, checking only those lines, which takes the same time on any
+        /// size of board
+        ///
+        /// # Panics
+        /// Panics if `coords` is not on the board
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let g: Game = "XXX/OO-/--- o".parse().expect("is a valid position");
+        /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+        /// assert_eq!(g.board().winner_through(&c(0, 1)), Some(TileValue::X));
+        /// assert_eq!(g.board().winner_through(&c(1, 1)), None);
+        /// assert_eq!(g.board().winner_through(&c(2, 2)), None);
+        /// ```
+        pub fn winner_through(&self, coords: &Coords) -> Option<TileValue> {
+            let value = (*self.value_at_coords(coords))?;
+            let tiles = match value {
+                TileValue::X => &self.x,
+                TileValue::O => &self.o,
+            };
+            let index = self.variant.index(coords);
+            self.lines
+                .through(index)
+                .any(|line| self.lines.filled(line, tiles, None))
+                .then_some(value)
+        }
+
+        /// Checks whether `value` playing in `coords` would fill a winning line,
+        /// looking only at the lines through `coords`, without changing the board
+        ///
+        /// # Panics
+        /// Panics if `coords` is not on the board
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let g: Game = "XX-/OO-/--- x".parse().expect("is a valid position");
+        /// let b = g.board();
+        /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+        /// assert!(b.completes_line(&c(0, 2), TileValue::X));
+        /// assert!(!b.completes_line(&c(0, 2), TileValue::O));
+        /// assert!(b.completes_line(&c(1, 2), TileValue::O));
+        /// assert!(!b.completes_line(&c(2, 2), TileValue::X));
+        /// ```
+        pub fn completes_line(&self, coords: &Coords, value: TileValue) -> bool {
+            assert!(
+                self.variant.contains(coords),
+                "{coords:?} is not on the board"
+            );
+            let index = self.variant.index(coords);
+            let tiles = match value {
+                TileValue::X => &self.x,
+                TileValue::O => &self.o,
+            };
+            self.lines
+                .through(index)
+                .any(|line| self.lines.filled(line, tiles, Some(index)))
+        }
+
+        /// Returns the open tiles that would fill a line through `coords` for the player
+        /// holding it: one for every line through `coords` that player has all but one
+        /// tile of, with that tile open
+        ///
+        /// Like `winner_through`, this only looks at the lines through `coords`
+        ///
+        /// # Panics
+        /// Panics if `coords` is not on the board
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let g: Game = "XX-/-OO/X-- o".parse().expect("is a valid position");
+        /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+        /// assert_eq!(g.board().completing_tiles(&c(0, 0)), [c(0, 2), c(1, 0)]);
+        /// assert_eq!(g.board().completing_tiles(&c(1, 1)), [c(1, 0)]);
+        /// assert!(g.board().completing_tiles(&c(2, 2)).is_empty());
+        /// ```
+        pub fn completing_tiles(&self, coords: &Coords) -> Vec<Coords> {
+            let Some(value) = *self.value_at_coords(coords) else {
+                return Vec::new();
+            };
+            let (own, other) = match value {
+                TileValue::X => (&self.x, &self.o),
+                TileValue::O => (&self.o, &self.x),
+            };
+            let width = self.variant.width as usize;
+            self.lines
+                .through(self.variant.index(coords))
+                .filter_map(|line| {
+                    let mut open = None;
+                    for index in self.lines.tiles(line) {
+                        if other.get(index) || (!own.get(index) && open.replace(index).is_some())
+                        {
+                            return None;
+                        }
+                    }
+                    open.map(|index| Coords((index / width) as u8, (index % width) as u8))
+                })
+                .collect()
         }
 
-        fn value_at_coords_mut(&mut self, coords: &Coords) -> &mut Option<TileValue> {
-            &mut self.0[coords.0 as usize][coords.1 as usize]
+        /// Checks whether every tile has been played in
+        pub fn is_full(&self) -> bool {
+            self.x.count() + self.o.count() == self.variant.area()
         }
 
+        /// Iterates over every tile and its value, row by row from the top left
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut b = Board::new();
+        /// b.set_tile(&Coords::build(0, 1).expect("is in bounds"), &Some(TileValue::O));
+        /// let (coords, value) = b.iter().nth(1).expect("The board has 9 tiles");
+        /// assert_eq!(coords, Coords::build(0, 1).expect("is in bounds"));
+        /// assert_eq!(value, Some(TileValue::O));
+        /// ```
+        pub fn iter(&self) -> impl Iterator<Item = (Coords, Option<TileValue>)> + '_ {
+            self.variant
+                .all_coords()
+                .map(|coords| (coords, *self.value_at_coords(&coords)))
+        }
+
+        /// Iterates over the coordinates of every tile nobody has played in yet
+        pub fn empty_tiles(&self) -> impl Iterator<Item = Coords> + '_ {
+            self.iter()
+                .filter(|(_, value)| value.is_none())
+                .map(|(coords, _)| coords)
+        }
+
+        /// Counts the tiles holding `value`
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut b = Board::new();
+        /// b.set_tile(&Coords::build(1, 1).expect("is in bounds"), &Some(TileValue::X));
+        /// assert_eq!(b.count(TileValue::X), 1);
+        /// assert_eq!(b.count(TileValue::O), 0);
+        /// assert_eq!(b.empty_tiles().count(), 8);
+        /// ```
+        pub fn count(&self, value: TileValue) -> usize {
+            match value {
+                TileValue::X => self.x.count(),
+                TileValue::O => self.o.count(),
+            }
+        }
+
+        /// # Panics
+        /// Panics if `coords` is not on the board
         pub fn set_tile(&mut self, coords: &Coords, value: &Option<TileValue>) {
-            *self.value_at_coords_mut(coords) = *value;
+            assert!(
+                self.variant.contains(coords),
+                "{coords:?} is not on the board"
+            );
+            if let Some(old) = self.value_at_coords(coords) {
+                self.hash ^= zobrist::tile_key(self.variant.index(coords), *old);
+            }
+            let index = self.variant.index(coords);
+            self.x.clear(index);
+            self.o.clear(index);
+            match value {
+                Some(TileValue::X) => self.x.set(index),
+                Some(TileValue::O) => self.o.set(index),
+                None => {}
+            }
+            if let Some(new) = value {
+                self.hash ^= zobrist::tile_key(index, *new);
+            }
+        }
+    }
+
+    impl Default for Board {
+        fn default() -> Self {
+            Self::new()
         }
     }
 
     /// Represents one turn of Tic-Tac-Toe, with a player playing `value` at `coords`
-    #[derive(Debug, PartialEq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
     pub struct Turn {
         value: TileValue,
         coords: Coords,
@@ -50,33 +703,78 @@ pub mod game {
         pub fn new(value: TileValue, coords: Coords) -> Self {
             Self { value, coords }
         }
+
+        pub fn value(&self) -> &TileValue {
+            &self.value
+        }
+
+        pub fn coords(&self) -> &Coords {
+            &self.coords
+        }
     }
 
     /// Represents and manages a game of Tic-Tac-Toe
-    #[derive(Debug)]
+    ///
+    /// Two games are equal only if their turns, including any undone ones, are the same;
+    /// compare `board` and `player_turn`, or `zobrist`, to compare just the positions
+    #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+    #[derive(Debug, Clone, PartialEq, Eq)]
     pub struct Game {
         board: Board,
         turn_history: Vec<Turn>,
+        /// Turns taken back with `undo`, most recently undone last
+        #[cfg_attr(feature = "serde", serde(skip_serializing))]
+        redo_stack: Vec<Turn>,
         player_turn: TileValue,
         result: Option<GameResult>,
+        /// Whether `take_turn` lets either player place a tile regardless of whose turn it is
+        free_placement: bool,
     }
 
     type TurnResult = Result<Option<GameResult>, TurnError>;
 
     impl Game {
-        /// Initializes a new game with an empty board, no turns, and turn X
+        /// Initializes a new game of classic 3x3 Tic-Tac-Toe with an empty board, no turns,
+        /// and turn X
         pub fn new() -> Self {
+            Self::with_variant(Variant::CLASSIC)
+        }
+
+        /// Initializes a new game played on `variant`'s board, with no turns and turn X
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let variant = Variant::build(4, 4, 4).expect("is a valid variant");
+        /// let mut g = Game::with_variant(variant);
+        /// let outside = Variant::build(5, 5, 4).expect("is a valid variant").coords(4, 4).expect("is in bounds");
+        /// assert!(matches!(g.play_coords(outside), Err(TurnError::OutOfBounds(_))));
+        /// for col in 0..3 {
+        ///     g.play_coords(variant.coords(0, col).expect("is in bounds")).expect("This tile is open and the game is not over");
+        ///     g.play_coords(variant.coords(1, col).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// }
+        /// assert!(g.result().is_none());
+        /// g.play_coords(variant.coords(0, 3).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// assert!(matches!(g.result(), Some(GameResult::Winner(TileValue::X))));
+        /// ```
+        pub fn with_variant(variant: Variant) -> Self {
             Self {
-                board: Board::new(),
+                board: Board::with_variant(variant),
                 turn_history: Vec::new(),
+                redo_stack: Vec::new(),
                 player_turn: TileValue::X,
                 result: None,
+                free_placement: false,
             }
         }
 
         /// Attempts to set the the tile at `turn.coords` to `turn.value`, and if
         /// the tile is already full then returns a `TurnError::TileFull` containing
-        /// the `TileValue` that is already in the tile
+        /// the coordinates and the `TileValue` that is already in the tile, or if the tile is not on the
+        /// board then returns a `TurnError::OutOfBounds`
+        ///
+        /// Unless free placement is turned on, `turn.value` must be the `player_turn`, or
+        /// a `TurnError::WrongPlayer` is returned. Afterwards it is the other player's turn
         ///
         /// # Examples
         /// ```rust
@@ -89,17 +787,69 @@ pub mod game {
         /// let value2 = TileValue::O;
         /// let coords2 = Coords::build(0, 0).expect("is in bounds");
         /// let turn2 = Turn::new(value2, coords2);
-        /// assert!(matches!(g.take_turn(turn2), Result::Err(TurnError::TileFull(TileValue::X))));
+        /// assert!(matches!(g.take_turn(turn2), Result::Err(TurnError::TileFull { value: TileValue::X, .. })));
+        /// let turn3 = Turn::new(TileValue::X, Coords::build(1, 1).expect("is in bounds"));
+        /// assert!(matches!(
+        ///     g.take_turn(turn3),
+        ///     Err(TurnError::WrongPlayer { expected: TileValue::O, got: TileValue::X })
+        /// ));
         /// ```
         pub fn take_turn(&mut self, turn: Turn) -> TurnResult {
             if let Some(x) = self.result {
                 return Err(TurnError::GameOver(x));
             }
+            if !self.free_placement && turn.value != self.player_turn {
+                return Err(TurnError::WrongPlayer {
+                    expected: self.player_turn,
+                    got: turn.value,
+                });
+            }
+            let result = self.apply_turn(turn)?;
+            self.redo_stack.clear();
+            Ok(result)
+        }
+
+        /// Lets either player place a tile on any turn, for setting up puzzles or
+        /// editing positions, or goes back to strictly alternating turns
+        ///
+        /// With free placement on, it is the other player's turn after each tile placed
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut g = Game::new();
+        /// g.set_free_placement(true);
+        /// g.take_turn(Turn::new(TileValue::X, Coords::build(0, 0).expect("is in bounds"))).expect("This tile is open and the game is not over");
+        /// g.take_turn(Turn::new(TileValue::X, Coords::build(1, 1).expect("is in bounds"))).expect("Free placement allows X twice");
+        /// assert_eq!(*g.player_turn(), TileValue::O);
+        /// g.set_free_placement(false);
+        /// assert!(g.take_turn(Turn::new(TileValue::X, Coords::build(2, 2).expect("is in bounds"))).is_err());
+        /// ```
+        pub fn set_free_placement(&mut self, free_placement: bool) {
+            self.free_placement = free_placement;
+        }
+
+        pub fn free_placement(&self) -> bool {
+            self.free_placement
+        }
+
+        /// Places the turn's tile without checking whose turn it is
+        fn apply_turn(&mut self, turn: Turn) -> TurnResult {
+            if let Some(x) = self.result {
+                return Err(TurnError::GameOver(x));
+            }
+            if !self.board.variant.contains(&turn.coords) {
+                return Err(TurnError::OutOfBounds(turn.coords));
+            }
             let val_ref: &Option<TileValue> = self.board.value_at_coords(&turn.coords);
             if let Some(val) = *val_ref {
-                Err(TurnError::TileFull(val))
+                Err(TurnError::TileFull {
+                    coords: turn.coords,
+                    value: val,
+                })
             } else {
                 self.board.set_tile(&turn.coords, &Some(turn.value));
+                self.player_turn = turn.value.toggle();
                 self.turn_history.push(turn);
                 Ok(self.check_and_update_result())
             }
@@ -113,7 +863,7 @@ pub mod game {
         /// let mut g = Game::new();
         /// let t1 = g.play_coords(Coords::build(0, 0).expect("is in bounds")).expect("Should not error, as tile [0,0] should be empty");
         /// let t2_first_try = g.play_coords(Coords::build(0, 0).expect("is in bounds"));
-        /// assert!(matches!(t2_first_try, Err(TurnError::TileFull(TileValue::X))));
+        /// assert!(matches!(t2_first_try, Err(TurnError::TileFull { value: TileValue::X, .. })));
         /// let t2_second_try = g.play_coords(Coords::build(0, 2).expect("is in bounds")).expect("Tile [1,0] should be empty and open for O to go there");
         /// assert!(matches!(*g.board().value_at_coords(&Coords::build(0, 2).expect("is in bounds")), Some(TileValue::O)));
         /// let t3 = g.play_coords(Coords::build(2, 2).expect("is in bounds"));
@@ -126,14 +876,93 @@ pub mod game {
         /// assert!(g.turn_history().iter().eq(turns.iter()));
         /// ```
         pub fn play_coords(&mut self, coords: Coords) -> TurnResult {
-            let result = self.take_turn(Turn {
+            self.take_turn(Turn {
                 value: self.player_turn,
                 coords,
-            })?;
-            self.player_turn = self.player_turn.toggle();
-            Ok(result)
+            })
         }
 
+        /// Takes back the most recent turn, restoring the board, `player_turn` and `result`
+        /// to how they were before it was played, and returns the undone turn
+        ///
+        /// The undone turn is kept so it can be replayed with `redo`, until a new turn is taken
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut g = Game::new();
+        /// let coords = Coords::build(1, 1).expect("is in bounds");
+        /// g.play_coords(coords).expect("This tile is open and the game is not over");
+        /// let undone = g.undo().expect("There is a turn to undo");
+        /// assert_eq!(*undone, Turn::new(TileValue::X, coords));
+        /// assert!(g.board().value_at_coords(&coords).is_none());
+        /// assert_eq!(*g.player_turn(), TileValue::X);
+        /// assert!(g.turn_history().is_empty());
+        /// assert!(g.undo().is_none());
+        /// ```
+        pub fn undo(&mut self) -> Option<&Turn> {
+            let turn = self.turn_history.pop()?;
+            self.board.set_tile(&turn.coords, &None);
+            self.player_turn = turn.value;
+            self.result = None;
+            self.check_and_update_result();
+            self.redo_stack.push(turn);
+            self.redo_stack.last()
+        }
+
+        /// Replays the most recently undone turn, returning it, or `None` if there is
+        /// nothing to redo
+        ///
+        /// Taking any new turn with `take_turn` or `play_coords` discards all undone turns
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut g = Game::new();
+        /// g.play_coords(Coords::build(0, 0).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// g.play_coords(Coords::build(1, 1).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// g.undo();
+        /// g.undo();
+        /// g.redo().expect("There is a turn to redo");
+        /// assert_eq!(g.turn_history().len(), 1);
+        /// assert_eq!(*g.player_turn(), TileValue::O);
+        /// g.play_coords(Coords::build(2, 2).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// assert!(g.redo().is_none());
+        /// ```
+        pub fn redo(&mut self) -> Option<&Turn> {
+            let turn = self.redo_stack.pop()?;
+            self.apply_turn(turn).expect(
+                "An undone turn should be legal to replay on the position it was undone from",
+            );
+            self.turn_history.last()
+        }
+
+        /// Undoes turns until only the first `ply` turns of `turn_history` remain,
+        /// returning how many turns were undone
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut g = Game::new();
+        /// for c in [(0, 0), (0, 1), (0, 2), (1, 0)] {
+        ///     g.play_coords(Coords::build(c.0, c.1).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// }
+        /// assert_eq!(g.undo_to(1), 3);
+        /// assert_eq!(g.turn_history().len(), 1);
+        /// assert_eq!(*g.player_turn(), TileValue::O);
+        /// assert_eq!(g.undo_to(5), 0);
+        /// ```
+        pub fn undo_to(&mut self, ply: usize) -> usize {
+            let mut undone = 0;
+            while self.turn_history.len() > ply {
+                self.undo();
+                undone += 1;
+            }
+            undone
+        }
+
+        /// The lines that win classic 3x3 Tic-Tac-Toe; other variants compute theirs
+        /// with `Variant::win_lines`
         pub const WIN_LINES: [[Coords; 3]; 8] = [
             [
                 Coords(0, 0),
@@ -231,44 +1060,54 @@ pub mod game {
             if self.result.is_some() {
                 return self.result;
             }
-            for line in Self::WIN_LINES {
-                let tile_line = line.iter().map(|coords: &Coords| -> &Option<TileValue> {
-                    self.board.value_at_coords(coords)
-                });
-                // println!("tile line pre check: {:?}", &tile_line);
-                if tile_line.clone().any(|tile| -> bool { tile.is_none() }) {
-                    continue;
-                }
-                // println!("tile line post chcek: {:?}", &tile_line);
-                let mut line_values = tile_line.map(|tile| -> TileValue{
-                    tile.expect("Should be `Some` because loop should have continued if any tiles in the line were none")
-                });
-                // println!("line values {:?}", &line_values);
-                if line_values
-                    .clone()
-                    .all(|tile| -> bool { tile == TileValue::X })
-                    || line_values
-                        .clone()
-                        .all(|tile| -> bool { tile == TileValue::O })
-                {
-                    return Some(GameResult::Winner(
-                        line_values
-                            .nth(0)
-                            .expect("Iterator should have a 0th element"),
-                    ));
-                }
+            // The game was not over before the last turn, so only that turn can have
+            // filled a line; a game set up from a position has to check them all
+            let winner = match self.turn_history.last() {
+                Some(turn) => self.board.winner_through(&turn.coords),
+                None => self.board.winner(),
+            };
+            if let Some(winner) = winner {
+                return Some(GameResult::Winner(winner));
             }
-            if self
-                .board
-                .0
-                .iter()
-                .all(|row| -> bool { row.iter().all(|tile| -> bool { tile.is_some() }) })
-            {
+            if self.board.is_full() {
                 return Some(GameResult::Tie);
             }
             None
         }
 
+        /// Returns every line filled by the winner, or nothing if nobody has won
+        ///
+        /// There can be more than one line if the winning move completed several at once
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut g = Game::new();
+        /// for c in [(0, 0), (1, 0), (0, 1), (1, 1), (1, 2), (2, 0), (2, 2), (2, 1)] {
+        ///     g.play_coords(Coords::build(c.0, c.1).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// }
+        /// assert!(g.winning_lines().is_empty());
+        /// g.play_coords(Coords::build(0, 2).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+        /// assert_eq!(g.winning_lines(), vec![
+        ///     vec![c(0, 0), c(0, 1), c(0, 2)],
+        ///     vec![c(0, 2), c(1, 2), c(2, 2)],
+        /// ]);
+        /// ```
+        pub fn winning_lines(&self) -> Vec<Vec<Coords>> {
+            let Some(GameResult::Winner(winner)) = self.result else {
+                return Vec::new();
+            };
+            self.board
+                .variant
+                .win_lines()
+                .filter(|line| {
+                    line.iter()
+                        .all(|coords| *self.board.value_at_coords(coords) == Some(winner))
+                })
+                .collect()
+        }
+
         pub fn check_and_update_result(&mut self) -> Option<GameResult> {
             self.result = self.check_end();
             self.result
@@ -285,6 +1124,40 @@ pub mod game {
             &self.board
         }
 
+        /// Returns the Zobrist hash of the position: the board and the player to move
+        ///
+        /// It is updated as turns are taken and undone, so it is cheap to call, and
+        /// positions reached through different orders of the same turns hash the same
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// use std::collections::HashSet;
+        /// let c = |row, col| Coords::build(row, col).expect("is in bounds");
+        /// let mut a = Game::new();
+        /// let mut b = Game::new();
+        /// for coords in [c(0, 0), c(1, 1), c(2, 2)] {
+        ///     a.play_coords(coords).expect("This tile is open and the game is not over");
+        /// }
+        /// for coords in [c(2, 2), c(1, 1), c(0, 0)] {
+        ///     b.play_coords(coords).expect("This tile is open and the game is not over");
+        /// }
+        /// assert_eq!(a.zobrist(), b.zobrist());
+        /// assert_ne!(a, b);
+        /// a.undo();
+        /// assert_ne!(a.zobrist(), b.zobrist());
+        /// a.redo();
+        /// let positions: HashSet<Game> = [a.clone(), a, b].into_iter().collect();
+        /// assert_eq!(positions.len(), 2);
+        /// assert_eq!(Game::new().zobrist(), 0);
+        /// ```
+        pub fn zobrist(&self) -> u64 {
+            match self.player_turn {
+                TileValue::X => self.board.hash,
+                TileValue::O => self.board.hash ^ zobrist::o_to_move_key(),
+            }
+        }
+
         pub fn turn_history(&self) -> &Vec<Turn> {
             &self.turn_history
         }
@@ -295,31 +1168,86 @@ pub mod game {
         pub fn result(&self) -> &Option<GameResult> {
             &self.result
         }
+
+        /// Iterates over every tile the current player could play in, which is none
+        /// of them once the game is over
+        ///
+        /// # Examples
+        /// ```rust
+        /// use ric_rac_roe_game::game::*;
+        /// let mut g = Game::new();
+        /// assert_eq!(g.legal_moves().count(), 9);
+        /// g.play_coords(Coords::build(1, 1).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// assert!(!g.legal_moves().any(|coords| coords == Coords::build(1, 1).expect("is in bounds")));
+        /// for c in [(0, 0), (0, 1), (2, 2), (2, 1)] {
+        ///     g.play_coords(Coords::build(c.0, c.1).expect("is in bounds")).expect("This tile is open and the game is not over");
+        /// }
+        /// assert!(g.result().is_some());
+        /// assert_eq!(g.legal_moves().count(), 0);
+        /// ```
+        pub fn legal_moves(&self) -> impl Iterator<Item = Coords> + '_ {
+            let game_over = self.result.is_some();
+            self.board.empty_tiles().filter(move |_| !game_over)
+        }
     }
+
+    impl Default for Game {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Hashes only the position, which is consistent with `Eq` since equal games are
+    /// always in the same position
+    impl Hash for Game {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.board.variant.hash(state);
+            state.write_u64(self.zobrist());
+        }
+    }
+
+    /// Draws the board, with the tiles of any winning line in brackets
     impl fmt::Display for Game {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            let tiles: &[[DisplayTileValueOption; 3]; 3] =
-                &self.board.0.map(|row| -> [DisplayTileValueOption; 3] {
-                    row.map(DisplayTileValueOption::from)
-                });
-            writeln!(f, "")?;
-            writeln!(f, "           |           |           ")?;
-            #[rustfmt::skip]
-            writeln!(f, "     {}     |     {}     |     {}  ",tiles[0][0], tiles[0][1], tiles[0][2])?;
-            writeln!(f, "           |           |           ")?;
-            writeln!(f, "-----------|-----------|-----------")?;
-            writeln!(f, "           |           |           ")?;
-            #[rustfmt::skip]
-            writeln!(f, "     {}     |     {}     |     {}  ",tiles[1][0], tiles[1][1], tiles[1][2])?;
-            writeln!(f, "           |           |           ")?;
-            writeln!(f, "-----------|-----------|-----------")?;
-            writeln!(f, "           |           |           ")?;
-            #[rustfmt::skip]
-            writeln!(f, "     {}     |     {}     |     {}  ",tiles[2][0], tiles[2][1], tiles[2][2])?;
-            writeln!(f, "           |           |           ")
-        }
-    }
-    #[derive(Debug, Clone, Copy, PartialEq)]
+            let width = self.board.variant.width as usize;
+            let winning_tiles: Vec<Coords> = self.winning_lines().into_iter().flatten().collect();
+            let blank = vec!["           "; width].join("|");
+            let divider = vec!["-----------"; width].join("|");
+            writeln!(f)?;
+            for (row, coords_row) in self
+                .board
+                .variant
+                .all_coords()
+                .collect::<Vec<_>>()
+                .chunks(width)
+                .enumerate()
+            {
+                if row > 0 {
+                    writeln!(f, "{divider}")?;
+                }
+                let mut tiles = coords_row
+                    .iter()
+                    .map(|coords| {
+                        let tile =
+                            DisplayTileValueOption::from(*self.board.value_at_coords(coords));
+                        if winning_tiles.contains(coords) {
+                            format!("    [{tile}]    ")
+                        } else {
+                            format!("     {tile}     ")
+                        }
+                    })
+                    .collect::<Vec<_>>()
+                    .join("|");
+                tiles.truncate(tiles.len() - 3);
+                writeln!(f, "{blank}")?;
+                writeln!(f, "{tiles}")?;
+                writeln!(f, "{blank}")?;
+            }
+            Ok(())
+        }
+    }
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub enum TileValue {
         X,
         O,
@@ -385,15 +1313,180 @@ pub mod game {
         }
     }
 
-    #[derive(Debug, Copy, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
     pub enum GameResult {
         Winner(TileValue),
         Tie,
     }
 
+    impl fmt::Display for GameResult {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                GameResult::Winner(winner) => write!(f, "{winner} won"),
+                GameResult::Tie => write!(f, "it was a tie"),
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     #[derive(Debug)]
     pub enum TurnError {
-        TileFull(TileValue),
+        /// The tile at `coords` already holds `value`
+        TileFull { coords: Coords, value: TileValue },
+        OutOfBounds(Coords),
+        WrongPlayer { expected: TileValue, got: TileValue },
         GameOver(GameResult),
     }
+
+    impl fmt::Display for TurnError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                TurnError::TileFull { coords, value } => {
+                    write!(f, "{value} is already in {coords}")
+                }
+                TurnError::OutOfBounds(coords) => write!(f, "{coords} is not on the board"),
+                TurnError::WrongPlayer { expected, got } => {
+                    write!(f, "it is {expected}'s turn, not {got}'s")
+                }
+                TurnError::GameOver(result) => write!(f, "the game is already over: {result}"),
+            }
+        }
+    }
+
+    impl error::Error for TurnError {}
+
+    /// Any error from this crate, so different operations can be chained with `?`
+    ///
+    /// # Examples
+    /// ```rust
+    /// use ric_rac_roe_game::game::*;
+    /// fn play_center(position: &str) -> Result<Game, Error> {
+    ///     let mut game: Game = position.parse()?;
+    ///     let center = game.board().variant().coords(1, 1)?;
+    ///     game.play_coords(center)?;
+    ///     Ok(game)
+    /// }
+    /// assert!(play_center("X--/---/--- o").is_ok());
+    /// let error = play_center("X--/-O-/--- x").expect_err("The center is taken");
+    /// assert!(matches!(error, Error::Turn(TurnError::TileFull { value: TileValue::O, .. })));
+    /// assert_eq!(error.to_string(), "O is already in b2");
+    /// assert!(matches!(play_center("X- o"), Err(Error::Coords(_))));
+    /// ```
+    #[derive(Debug)]
+    pub enum Error {
+        Coords(CoordsBuildError),
+        ParseCoords(ParseCoordsError),
+        Variant(VariantBuildError),
+        ParseVariant(ParseVariantError),
+        ParseDifficulty(ParseDifficultyError),
+        Turn(TurnError),
+        Position(PositionError),
+        Record(RecordError),
+        Solution(SolutionError),
+        Menace(MenaceError),
+        Cli(CliError),
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Error::Coords(error) => error.fmt(f),
+                Error::ParseCoords(error) => error.fmt(f),
+                Error::Variant(error) => error.fmt(f),
+                Error::ParseVariant(error) => error.fmt(f),
+                Error::ParseDifficulty(error) => error.fmt(f),
+                Error::Turn(error) => error.fmt(f),
+                Error::Position(error) => error.fmt(f),
+                Error::Record(error) => error.fmt(f),
+                Error::Solution(error) => error.fmt(f),
+                Error::Menace(error) => error.fmt(f),
+                Error::Cli(error) => error.fmt(f),
+            }
+        }
+    }
+
+    impl error::Error for Error {
+        fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+            match self {
+                Error::Coords(error) => error.source(),
+                Error::ParseCoords(error) => error.source(),
+                Error::Variant(error) => error.source(),
+                Error::ParseVariant(error) => error.source(),
+                Error::ParseDifficulty(error) => error.source(),
+                Error::Turn(error) => error.source(),
+                Error::Position(error) => error.source(),
+                Error::Record(error) => error.source(),
+                Error::Solution(error) => error.source(),
+                Error::Menace(error) => error.source(),
+                Error::Cli(error) => error.source(),
+            }
+        }
+    }
+
+    impl From<CoordsBuildError> for Error {
+        fn from(error: CoordsBuildError) -> Self {
+            Error::Coords(error)
+        }
+    }
+
+    impl From<ParseCoordsError> for Error {
+        fn from(error: ParseCoordsError) -> Self {
+            Error::ParseCoords(error)
+        }
+    }
+
+    impl From<VariantBuildError> for Error {
+        fn from(error: VariantBuildError) -> Self {
+            Error::Variant(error)
+        }
+    }
+
+    impl From<ParseVariantError> for Error {
+        fn from(error: ParseVariantError) -> Self {
+            Error::ParseVariant(error)
+        }
+    }
+
+    impl From<ParseDifficultyError> for Error {
+        fn from(error: ParseDifficultyError) -> Self {
+            Error::ParseDifficulty(error)
+        }
+    }
+
+    impl From<TurnError> for Error {
+        fn from(error: TurnError) -> Self {
+            Error::Turn(error)
+        }
+    }
+
+    impl From<PositionError> for Error {
+        fn from(error: PositionError) -> Self {
+            Error::Position(error)
+        }
+    }
+
+    impl From<RecordError> for Error {
+        fn from(error: RecordError) -> Self {
+            Error::Record(error)
+        }
+    }
+
+    impl From<SolutionError> for Error {
+        fn from(error: SolutionError) -> Self {
+            Error::Solution(error)
+        }
+    }
+
+    impl From<MenaceError> for Error {
+        fn from(error: MenaceError) -> Self {
+            Error::Menace(error)
+        }
+    }
+
+    impl From<CliError> for Error {
+        fn from(error: CliError) -> Self {
+            Error::Cli(error)
+        }
+    }
 }